@@ -0,0 +1,92 @@
+use crate::contract::MyVault;
+use crate::roles::{assert_any_role, Role};
+use crate::state::MyState;
+use cosmwasm_std::{ensure, Addr, DepsMut, Event, MessageInfo, Response};
+use vaultenator::admin::Administer;
+use vaultenator::errors::ContractError;
+use vaultenator::state::ManageState;
+
+// Pausers manage the vault status alongside the owner, guardians and keepers
+// can also pause the vault so people and bots watching alerts can react to
+// incidents without owner keys. The execute entry point records pause history
+// around these in src/pause.rs.
+impl Administer<MyState> for MyVault {
+    fn handle_open_contract(
+        &self,
+        mut deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        open_contract(&mut deps, &info.sender)?;
+
+        Ok(Response::new().add_event(Event::new("open_contract")))
+    }
+
+    fn handle_pause_contract(
+        &self,
+        mut deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        pause_contract(&mut deps, &info.sender)?;
+
+        Ok(Response::default().add_event(Event::new("paused")))
+    }
+
+    fn handle_unpause_contract(
+        &self,
+        mut deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        unpause_contract(&mut deps, &info.sender)?;
+
+        Ok(Response::default().add_event(Event::new("unpaused")))
+    }
+}
+
+/// Opens and unpauses the vault, returning the updated state.
+pub(crate) fn open_contract(deps: &mut DepsMut, sender: &Addr) -> Result<MyState, ContractError> {
+    assert_any_role(deps.as_ref(), sender, &[Role::Pauser])?;
+
+    let mut state = MyState::get_from_storage(deps.as_ref())?;
+    ensure!(!state.is_open, ContractError::IsOpen {});
+
+    state.set_open(true);
+    state.set_paused(false);
+    state.save_to_storage(deps)?;
+
+    Ok(state)
+}
+
+/// Pauses the vault, returning the updated state. A pause that lapsed after
+/// the maximum pause duration cannot be renewed, only an unpaused vault can
+/// be paused again.
+pub(crate) fn pause_contract(deps: &mut DepsMut, sender: &Addr) -> Result<MyState, ContractError> {
+    assert_any_role(
+        deps.as_ref(),
+        sender,
+        &[Role::Pauser, Role::Guardian, Role::Keeper],
+    )?;
+
+    let mut state = MyState::get_from_storage(deps.as_ref())?;
+    ensure!(!state.is_paused, ContractError::Paused {});
+
+    state.set_paused(true);
+    state.save_to_storage(deps)?;
+
+    Ok(state)
+}
+
+/// Unpauses the vault, returning the updated state.
+pub(crate) fn unpause_contract(
+    deps: &mut DepsMut,
+    sender: &Addr,
+) -> Result<MyState, ContractError> {
+    assert_any_role(deps.as_ref(), sender, &[Role::Pauser])?;
+
+    let mut state = MyState::get_from_storage(deps.as_ref())?;
+    ensure!(state.is_paused, ContractError::NotPaused {});
+
+    state.set_paused(false);
+    state.save_to_storage(deps)?;
+
+    Ok(state)
+}
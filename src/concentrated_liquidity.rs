@@ -0,0 +1,697 @@
+use crate::config::MyConfig;
+use crate::errors::ContractError;
+use crate::pricing::VIRTUAL_SHARES;
+use crate::state::MyState;
+use crate::strategy::{
+    display_coins, finish_deposit, finish_withdrawal, min_amount_out, query_balance, twap_price,
+    validate_twap_params, PendingWithdrawal, PENDING_WITHDRAWAL,
+};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    ensure, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env, Event, Reply, Response, StdError,
+    StdResult, SubMsg, Uint128,
+};
+use cw_storage_plus::Item;
+use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::{
+    FullPositionBreakdown, MsgAddToPosition, MsgAddToPositionResponse, MsgCollectIncentives,
+    MsgCollectSpreadRewards, MsgCreatePosition, MsgCreatePositionResponse, MsgWithdrawPosition,
+    MsgWithdrawPositionResponse, Pool, PositionByIdRequest,
+};
+use osmosis_std::types::osmosis::poolmanager::v1beta1::{
+    MsgSwapExactAmountIn, MsgSwapExactAmountInResponse, PoolRequest, SwapAmountInRoute,
+};
+use std::str::FromStr;
+use vaultenator::errors::ContractError as VaultenatorError;
+use vaultenator::state::ManageState;
+
+pub const CL_INVEST_SWAP_REPLY_ID: u64 = 6;
+pub const CL_CREATE_POSITION_REPLY_ID: u64 = 7;
+pub const CL_ADD_TO_POSITION_REPLY_ID: u64 = 8;
+pub const CL_WITHDRAW_POSITION_REPLY_ID: u64 = 9;
+pub const CL_DIVEST_SWAP_REPLY_ID: u64 = 10;
+pub const CL_REBALANCE_REPLY_ID: u64 = 11;
+
+/// Ticks covering each power of ten of the price
+const TICKS_PER_POWER_OF_TEN: i64 = 9_000_000;
+/// Exponent of the price increment per tick between prices one and ten
+const EXPONENT_AT_PRICE_ONE: i64 = -6;
+/// Lowest tick a position can use, a price of 10^-12. The price increment per
+/// tick is 10^-18 just above it, the smallest `Decimal256` holds
+pub const MIN_TICK: i64 = -108_000_000;
+/// Highest tick a position can use, a price of 10^38
+pub const MAX_TICK: i64 = 342_000_000;
+
+/// Base tokens waiting to be added to the position with the output of the
+/// invest swap
+pub const PENDING_POSITION: Item<Uint128> = Item::new("cl_pending_position");
+
+/// Provides liquidity between `lower_tick` and `upper_tick` in a
+/// concentrated liquidity pool pairing the base denom with one other asset.
+/// The vault holds a single position, tracked in `MyState`.
+#[cw_serde]
+pub struct ConcentratedLiquidityStrategy {
+    pub pool_id: u64,
+    pub token0: String,
+    pub token1: String,
+    pub lower_tick: i64,
+    pub upper_tick: i64,
+    /// Seconds the pool price is averaged over
+    pub twap_window: u64,
+    /// Share of the amounts the TWAP implies swaps and positions may lose
+    pub max_slippage: Decimal,
+}
+
+impl ConcentratedLiquidityStrategy {
+    /// Checks the pool is a concentrated liquidity pool containing
+    /// `base_denom` and the tick range is valid for it.
+    pub fn new(
+        deps: Deps,
+        pool_id: u64,
+        base_denom: &str,
+        lower_tick: i64,
+        upper_tick: i64,
+        twap_window: u64,
+        max_slippage: Decimal,
+    ) -> Result<Self, ContractError> {
+        validate_twap_params(twap_window, max_slippage)?;
+
+        let pool = query_pool(deps, pool_id)?;
+
+        ensure!(
+            pool.token0 == base_denom || pool.token1 == base_denom,
+            ContractError::InvalidPool { pool_id }
+        );
+        validate_range(&pool, lower_tick, upper_tick)?;
+
+        Ok(Self {
+            pool_id,
+            token0: pool.token0,
+            token1: pool.token1,
+            lower_tick,
+            upper_tick,
+            twap_window,
+            max_slippage,
+        })
+    }
+
+    pub fn pair_denom(&self, base_denom: &str) -> &str {
+        if self.token0 == base_denom {
+            &self.token1
+        } else {
+            &self.token0
+        }
+    }
+
+    /// Moves the strategy to a new tick range, existing liquidity is only
+    /// moved by `rebalance`.
+    pub fn set_range(
+        &mut self,
+        deps: Deps,
+        lower_tick: i64,
+        upper_tick: i64,
+    ) -> Result<(), ContractError> {
+        let pool = query_pool(deps, self.pool_id)?;
+        validate_range(&pool, lower_tick, upper_tick)?;
+
+        self.lower_tick = lower_tick;
+        self.upper_tick = upper_tick;
+
+        Ok(())
+    }
+
+    /// Value in base tokens of the position and pair tokens held by the
+    /// vault. The position is valued at the amounts its liquidity holds when
+    /// the pool trades at its TWAP, swapping against the pool moves the
+    /// amounts but not the liquidity so it cannot move the value within a
+    /// block.
+    pub fn value(
+        &self,
+        deps: Deps,
+        env: &Env,
+        base_denom: &str,
+    ) -> Result<Uint128, VaultenatorError> {
+        let pair_denom = self.pair_denom(base_denom);
+        let pair_amount = query_balance(deps, env, pair_denom)?;
+        let position = query_position(deps)?.and_then(|p| p.position);
+
+        if position.is_none() && pair_amount.is_zero() {
+            return Ok(Uint128::zero());
+        }
+
+        let price = self.twap_price(deps, env, &self.token0, &self.token1)?;
+
+        let (mut amount0, mut amount1) = match position {
+            Some(position) => amounts_for_liquidity(
+                Decimal256::from_str(&position.liquidity)?,
+                price,
+                position.lower_tick,
+                position.upper_tick,
+            )?,
+            None => (Decimal256::zero(), Decimal256::zero()),
+        };
+
+        let pair_amount = Decimal256::from_ratio(pair_amount, 1u128);
+        let price = Decimal256::from(price);
+        let value = if self.token0 == base_denom {
+            amount1 += pair_amount;
+            amount0 + amount1 / price
+        } else {
+            amount0 += pair_amount;
+            amount1 + amount0 * price
+        };
+
+        Ok(to_amount(value)?)
+    }
+
+    /// Swaps half of `amount` base tokens for pair tokens, adding both to the
+    /// position once the swap replies.
+    pub fn invest(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        base_denom: &str,
+        amount: Uint128,
+    ) -> StdResult<Vec<SubMsg>> {
+        let swap_amount = amount.multiply_ratio(1u128, 2u128);
+        if swap_amount.is_zero() {
+            return Ok(vec![]);
+        }
+
+        PENDING_POSITION.save(deps.storage, &(amount - swap_amount))?;
+
+        let msg = self.swap_msg(
+            deps.as_ref(),
+            env,
+            base_denom,
+            self.pair_denom(base_denom),
+            swap_amount,
+        )?;
+
+        Ok(vec![SubMsg::reply_on_success(msg, CL_INVEST_SWAP_REPLY_ID)])
+    }
+
+    /// Withdraws the share of the position's liquidity owned by `shares`
+    /// strategy tokens and swaps the pair tokens received back to base
+    /// tokens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw(
+        &self,
+        response: Response,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        mut withdrawal: PendingWithdrawal,
+        shares: Uint128,
+        total_supply: Uint128,
+    ) -> Result<Response, ContractError> {
+        let share = Decimal256::from_ratio(shares, total_supply + VIRTUAL_SHARES);
+
+        withdrawal.pair_amount =
+            query_balance(deps.as_ref(), env, self.pair_denom(&config.base_denom))?
+                .multiply_ratio(shares, total_supply + VIRTUAL_SHARES);
+
+        let Some(position) = query_position(deps.as_ref())?.and_then(|p| p.position) else {
+            return self.swap_to_base(response, deps, env, config, withdrawal);
+        };
+
+        let liquidity = Decimal256::from_str(&position.liquidity)? * share;
+        if liquidity.is_zero() {
+            return self.swap_to_base(response, deps, env, config, withdrawal);
+        }
+
+        PENDING_WITHDRAWAL.save(deps.storage, &withdrawal)?;
+
+        let msg = MsgWithdrawPosition {
+            position_id: position.position_id,
+            sender: env.contract.address.to_string(),
+            liquidity_amount: liquidity.to_string(),
+        };
+
+        Ok(response.add_submessage(SubMsg::reply_on_success(msg, CL_WITHDRAW_POSITION_REPLY_ID)))
+    }
+
+    /// Withdraws the whole position so it can be recreated in the current
+    /// tick range once the withdrawal replies.
+    pub fn rebalance(&self, mut deps: DepsMut, env: &Env) -> Result<Response, ContractError> {
+        let Some(position) = query_position(deps.as_ref())?.and_then(|p| p.position) else {
+            return Ok(Response::new());
+        };
+
+        // Withdrawing all liquidity closes the position
+        let mut state = MyState::get_from_storage(deps.as_ref())?;
+        state.position_id = None;
+        state.save_to_storage(&mut deps)?;
+
+        let msg = MsgWithdrawPosition {
+            position_id: position.position_id,
+            sender: env.contract.address.to_string(),
+            liquidity_amount: position.liquidity,
+        };
+
+        Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, CL_REBALANCE_REPLY_ID)))
+    }
+
+    /// Messages collecting the spread rewards and incentives earned by the
+    /// vault's position.
+    pub fn claim_msgs(&self, deps: Deps, env: &Env) -> Result<Vec<CosmosMsg>, ContractError> {
+        let state = MyState::get_from_storage(deps)?;
+        let Some(position_id) = state.position_id else {
+            return Ok(vec![]);
+        };
+
+        Ok(vec![
+            MsgCollectSpreadRewards {
+                position_ids: vec![position_id],
+                sender: env.contract.address.to_string(),
+            }
+            .into(),
+            MsgCollectIncentives {
+                position_ids: vec![position_id],
+                sender: env.contract.address.to_string(),
+            }
+            .into(),
+        ])
+    }
+
+    pub fn handle_reply(
+        &self,
+        mut deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        match msg.id {
+            CL_INVEST_SWAP_REPLY_ID => {
+                let response: MsgSwapExactAmountInResponse = msg.result.try_into()?;
+
+                let base_amount = PENDING_POSITION.load(deps.storage)?;
+                PENDING_POSITION.remove(deps.storage);
+                let pair_amount = Uint128::from_str(&response.token_out_amount)?;
+
+                let (amount0, amount1) =
+                    self.order_amounts(&config.base_denom, base_amount, pair_amount);
+                self.provide_liquidity(deps, env, config, amount0, amount1)
+            }
+            CL_CREATE_POSITION_REPLY_ID => {
+                let response: MsgCreatePositionResponse = msg.result.try_into()?;
+                self.save_position_id(&mut deps, response.position_id)?;
+
+                let event = Event::new("create_position").add_attributes(vec![
+                    ("pool_id", self.pool_id.to_string()),
+                    ("position_id", response.position_id.to_string()),
+                    ("lower_tick", response.lower_tick.to_string()),
+                    ("upper_tick", response.upper_tick.to_string()),
+                    ("liquidity_created", response.liquidity_created),
+                    ("amount0", response.amount0),
+                    ("amount1", response.amount1),
+                ]);
+
+                finish_deposit(Response::new().add_event(event), deps, env, config)
+            }
+            CL_ADD_TO_POSITION_REPLY_ID => {
+                let response: MsgAddToPositionResponse = msg.result.try_into()?;
+                // Adding to a position replaces it with a new one
+                self.save_position_id(&mut deps, response.position_id)?;
+
+                let event = Event::new("add_to_position").add_attributes(vec![
+                    ("pool_id", self.pool_id.to_string()),
+                    ("position_id", response.position_id.to_string()),
+                    ("amount0", response.amount0),
+                    ("amount1", response.amount1),
+                ]);
+
+                finish_deposit(Response::new().add_event(event), deps, env, config)
+            }
+            CL_WITHDRAW_POSITION_REPLY_ID => {
+                let response: MsgWithdrawPositionResponse = msg.result.try_into()?;
+
+                let mut withdrawal = PENDING_WITHDRAWAL.load(deps.storage)?;
+                let tokens_out = self.withdrawn_coins(&response);
+                for token in &tokens_out {
+                    let amount = Uint128::from_str(&token.amount)?;
+                    if token.denom == config.base_denom {
+                        withdrawal.base_amount += amount;
+                    } else {
+                        withdrawal.pair_amount += amount;
+                    }
+                }
+
+                let event = Event::new("withdraw_position").add_attributes(vec![
+                    ("pool_id", self.pool_id.to_string()),
+                    ("token_out", display_coins(&tokens_out)),
+                ]);
+
+                self.swap_to_base(
+                    Response::new().add_event(event),
+                    deps,
+                    env,
+                    config,
+                    withdrawal,
+                )
+            }
+            CL_DIVEST_SWAP_REPLY_ID => {
+                let response: MsgSwapExactAmountInResponse = msg.result.try_into()?;
+
+                let mut withdrawal = PENDING_WITHDRAWAL.load(deps.storage)?;
+                PENDING_WITHDRAWAL.remove(deps.storage);
+
+                withdrawal.base_amount += Uint128::from_str(&response.token_out_amount)?;
+                withdrawal.pair_amount = Uint128::zero();
+
+                finish_withdrawal(Response::new(), deps, env, config, withdrawal)
+            }
+            CL_REBALANCE_REPLY_ID => {
+                let response: MsgWithdrawPositionResponse = msg.result.try_into()?;
+
+                self.provide_liquidity(
+                    deps,
+                    env,
+                    config,
+                    Uint128::from_str(&response.amount0)?,
+                    Uint128::from_str(&response.amount1)?,
+                )
+            }
+            _ => Err(VaultenatorError::InvalidReplyId.into()),
+        }
+    }
+
+    /// Adds the amounts to the vault's position, creating one in the current
+    /// tick range if there is none. Tokens the range cannot use stay on the
+    /// contract, and the position reverts if it takes `max_slippage` less of
+    /// either token than it would at the TWAP.
+    fn provide_liquidity(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        amount0: Uint128,
+        amount1: Uint128,
+    ) -> Result<Response, ContractError> {
+        if amount0.is_zero() && amount1.is_zero() {
+            return finish_deposit(Response::new(), deps, env, config);
+        }
+
+        let state = MyState::get_from_storage(deps.as_ref())?;
+        let (min_amount0, min_amount1) = self.min_amounts(deps.as_ref(), env, amount0, amount1)?;
+
+        let sub_msg = match state.position_id {
+            Some(position_id) => SubMsg::reply_on_success(
+                MsgAddToPosition {
+                    position_id,
+                    sender: env.contract.address.to_string(),
+                    amount0: amount0.to_string(),
+                    amount1: amount1.to_string(),
+                    token_min_amount0: min_amount0.to_string(),
+                    token_min_amount1: min_amount1.to_string(),
+                },
+                CL_ADD_TO_POSITION_REPLY_ID,
+            ),
+            None => {
+                let mut tokens_provided: Vec<OsmosisCoin> =
+                    [(&self.token0, amount0), (&self.token1, amount1)]
+                        .into_iter()
+                        .filter(|(_, amount)| !amount.is_zero())
+                        .map(|(denom, amount)| OsmosisCoin {
+                            denom: denom.to_string(),
+                            amount: amount.to_string(),
+                        })
+                        .collect();
+                // Coins must be sorted by denom
+                tokens_provided.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+                SubMsg::reply_on_success(
+                    MsgCreatePosition {
+                        pool_id: self.pool_id,
+                        sender: env.contract.address.to_string(),
+                        lower_tick: self.lower_tick,
+                        upper_tick: self.upper_tick,
+                        tokens_provided,
+                        token_min_amount0: min_amount0.to_string(),
+                        token_min_amount1: min_amount1.to_string(),
+                    },
+                    CL_CREATE_POSITION_REPLY_ID,
+                )
+            }
+        };
+
+        Ok(Response::new().add_submessage(sub_msg))
+    }
+
+    /// Swaps the withdrawal's pair tokens to base tokens, finishing the
+    /// withdrawal straight away if there are none.
+    fn swap_to_base(
+        &self,
+        response: Response,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        withdrawal: PendingWithdrawal,
+    ) -> Result<Response, ContractError> {
+        if withdrawal.pair_amount.is_zero() {
+            PENDING_WITHDRAWAL.remove(deps.storage);
+            return finish_withdrawal(response, deps, env, config, withdrawal);
+        }
+
+        PENDING_WITHDRAWAL.save(deps.storage, &withdrawal)?;
+
+        let msg = self.swap_msg(
+            deps.as_ref(),
+            env,
+            self.pair_denom(&config.base_denom),
+            &config.base_denom,
+            withdrawal.pair_amount,
+        )?;
+
+        Ok(response.add_submessage(SubMsg::reply_on_success(msg, CL_DIVEST_SWAP_REPLY_ID)))
+    }
+
+    /// Least of each token a position in the strategy's range may take from
+    /// `amount0` and `amount1`, allowing for `max_slippage` from the amounts
+    /// it takes at the TWAP.
+    fn min_amounts(
+        &self,
+        deps: Deps,
+        env: &Env,
+        amount0: Uint128,
+        amount1: Uint128,
+    ) -> Result<(Uint128, Uint128), ContractError> {
+        let price = self.twap_price(deps, env, &self.token0, &self.token1)?;
+
+        let liquidity = liquidity_for_amounts(
+            Decimal256::from_ratio(amount0, 1u128),
+            Decimal256::from_ratio(amount1, 1u128),
+            price,
+            self.lower_tick,
+            self.upper_tick,
+        )?;
+        let (used0, used1) =
+            amounts_for_liquidity(liquidity, price, self.lower_tick, self.upper_tick)?;
+
+        let tolerance = Decimal::one() - self.max_slippage;
+
+        Ok((
+            to_amount(used0)?.mul_floor(tolerance),
+            to_amount(used1)?.mul_floor(tolerance),
+        ))
+    }
+
+    fn twap_price(
+        &self,
+        deps: Deps,
+        env: &Env,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> StdResult<Decimal> {
+        twap_price(
+            deps,
+            env,
+            self.pool_id,
+            base_asset,
+            quote_asset,
+            self.twap_window,
+        )
+    }
+
+    /// Swaps `amount` through the pool, reverting if it returns more than
+    /// `max_slippage` less than the TWAP implies.
+    fn swap_msg(
+        &self,
+        deps: Deps,
+        env: &Env,
+        denom_in: &str,
+        denom_out: &str,
+        amount: Uint128,
+    ) -> StdResult<MsgSwapExactAmountIn> {
+        let price = self.twap_price(deps, env, denom_in, denom_out)?;
+
+        Ok(MsgSwapExactAmountIn {
+            sender: env.contract.address.to_string(),
+            routes: vec![SwapAmountInRoute {
+                pool_id: self.pool_id,
+                token_out_denom: denom_out.to_string(),
+            }],
+            token_in: Some(OsmosisCoin {
+                denom: denom_in.to_string(),
+                amount: amount.to_string(),
+            }),
+            token_out_min_amount: min_amount_out(amount, price, self.max_slippage).to_string(),
+        })
+    }
+
+    fn order_amounts(
+        &self,
+        base_denom: &str,
+        base_amount: Uint128,
+        pair_amount: Uint128,
+    ) -> (Uint128, Uint128) {
+        if self.token0 == base_denom {
+            (base_amount, pair_amount)
+        } else {
+            (pair_amount, base_amount)
+        }
+    }
+
+    fn withdrawn_coins(&self, response: &MsgWithdrawPositionResponse) -> Vec<OsmosisCoin> {
+        [
+            (&self.token0, &response.amount0),
+            (&self.token1, &response.amount1),
+        ]
+        .into_iter()
+        .map(|(denom, amount)| OsmosisCoin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        })
+        .collect()
+    }
+
+    fn save_position_id(&self, deps: &mut DepsMut, position_id: u64) -> Result<(), ContractError> {
+        let mut state = MyState::get_from_storage(deps.as_ref())?;
+        state.position_id = Some(position_id);
+        Ok(state.save_to_storage(deps)?)
+    }
+}
+
+fn query_pool(deps: Deps, pool_id: u64) -> Result<Pool, ContractError> {
+    PoolRequest { pool_id }
+        .query(&deps.querier)?
+        .pool
+        .filter(|pool| pool.type_url == Pool::TYPE_URL)
+        .and_then(|pool| Pool::try_from(pool).ok())
+        .ok_or(ContractError::InvalidPool { pool_id })
+}
+
+fn validate_range(pool: &Pool, lower_tick: i64, upper_tick: i64) -> Result<(), ContractError> {
+    let tick_spacing = pool.tick_spacing as i64;
+
+    // Prices outside the bounds would round to zero or overflow
+    ensure!(
+        MIN_TICK <= lower_tick
+            && lower_tick < upper_tick
+            && upper_tick <= MAX_TICK
+            && lower_tick % tick_spacing == 0
+            && upper_tick % tick_spacing == 0,
+        ContractError::InvalidTickRange {
+            lower_tick,
+            upper_tick
+        }
+    );
+
+    Ok(())
+}
+
+/// The vault's position, `None` if it has not created one yet.
+fn query_position(deps: Deps) -> Result<Option<FullPositionBreakdown>, VaultenatorError> {
+    let state = MyState::get_from_storage(deps)?;
+
+    let Some(position_id) = state.position_id else {
+        return Ok(None);
+    };
+
+    Ok(PositionByIdRequest { position_id }
+        .query(&deps.querier)?
+        .position)
+}
+
+/// Price of token0 in token1 at `tick`. Each run of
+/// `TICKS_PER_POWER_OF_TEN` ticks adds a fixed increment to the price, ten
+/// times larger for every power of ten the price has grown by.
+fn tick_to_price(tick: i64) -> Decimal256 {
+    let powers = tick / TICKS_PER_POWER_OF_TEN;
+    let mut exponent = EXPONENT_AT_PRICE_ONE + powers;
+    // Below a price of one each tick is a tenth of the increment above it
+    if tick < 0 {
+        exponent -= 1;
+    }
+
+    let additive_ticks = tick - powers * TICKS_PER_POWER_OF_TEN;
+    let increment =
+        pow_ten(exponent) * Decimal256::from_ratio(additive_ticks.unsigned_abs(), 1u128);
+
+    if additive_ticks < 0 {
+        pow_ten(powers) - increment
+    } else {
+        pow_ten(powers) + increment
+    }
+}
+
+fn pow_ten(exponent: i64) -> Decimal256 {
+    let power = 10u128.pow(exponent.unsigned_abs() as u32);
+    if exponent < 0 {
+        Decimal256::from_ratio(1u128, power)
+    } else {
+        Decimal256::from_ratio(power, 1u128)
+    }
+}
+
+/// Token amounts `liquidity` between `lower_tick` and `upper_tick` holds when
+/// token0 trades at `price` token1.
+fn amounts_for_liquidity(
+    liquidity: Decimal256,
+    price: Decimal,
+    lower_tick: i64,
+    upper_tick: i64,
+) -> StdResult<(Decimal256, Decimal256)> {
+    let sqrt_lower = tick_to_price(lower_tick).sqrt();
+    let sqrt_upper = tick_to_price(upper_tick).sqrt();
+    let sqrt_price = Decimal256::from(price).sqrt().clamp(sqrt_lower, sqrt_upper);
+
+    if sqrt_price.is_zero() {
+        return Err(StdError::generic_err("Price out of range"));
+    }
+
+    Ok((
+        liquidity * (sqrt_upper - sqrt_price) / (sqrt_price * sqrt_upper),
+        liquidity * (sqrt_price - sqrt_lower),
+    ))
+}
+
+/// Liquidity `amount0` and `amount1` provide between `lower_tick` and
+/// `upper_tick` when token0 trades at `price` token1, limited by whichever
+/// token runs out first.
+fn liquidity_for_amounts(
+    amount0: Decimal256,
+    amount1: Decimal256,
+    price: Decimal,
+    lower_tick: i64,
+    upper_tick: i64,
+) -> StdResult<Decimal256> {
+    let sqrt_lower = tick_to_price(lower_tick).sqrt();
+    let sqrt_upper = tick_to_price(upper_tick).sqrt();
+    let sqrt_price = Decimal256::from(price).sqrt().clamp(sqrt_lower, sqrt_upper);
+
+    // Below the range only token0 is provided and above it only token1
+    let liquidity0 = (sqrt_price < sqrt_upper)
+        .then(|| amount0 * sqrt_price * sqrt_upper / (sqrt_upper - sqrt_price));
+    let liquidity1 = (sqrt_price > sqrt_lower).then(|| amount1 / (sqrt_price - sqrt_lower));
+
+    match (liquidity0, liquidity1) {
+        (Some(liquidity0), Some(liquidity1)) => Ok(liquidity0.min(liquidity1)),
+        (Some(liquidity), None) | (None, Some(liquidity)) => Ok(liquidity),
+        (None, None) => Err(StdError::generic_err("Price out of range")),
+    }
+}
+
+fn to_amount(amount: Decimal256) -> StdResult<Uint128> {
+    Ok(amount.to_uint_floor().try_into()?)
+}
@@ -1,15 +1,49 @@
-use crate::msg::InstantiateMsg;
+use crate::errors::ContractError as MyContractError;
+use crate::fees::{MAX_DEPOSIT_FEE, MAX_MANAGEMENT_FEE, MAX_PERFORMANCE_FEE, MAX_WITHDRAWAL_FEE};
+use crate::harvest::{validate_reward_routes, RewardRoute};
+use crate::msg::{InstantiateMsg, UpdateConfigMsg};
+use crate::strategy::Strategy;
+use crate::timelock::MAX_CONFIG_TIMELOCK;
 use cosmwasm_schema::cw_serde;
 use serde::{de::DeserializeOwned, Serialize};
 
 use vaultenator::config::Configure;
 use vaultenator::errors::ContractError;
 
-use cosmwasm_std::{DepsMut, Uint128};
+use cosmwasm_std::{attr, ensure, Addr, Api, Attribute, Decimal, DepsMut, Uint128};
+use cw_utils::Duration;
 
 #[cw_serde]
 pub struct MyConfig {
-    pub strategy_cap: Uint128,
+    /// Maximum total assets the vault accepts, uncapped if `None`
+    pub strategy_cap: Option<Uint128>,
+    /// Time vault tokens are locked for between `Unlock` and
+    /// `WithdrawUnlocked`, a zero duration allows instant `Redeem`
+    pub lockup_duration: Duration,
+    /// Share of deposited base tokens taken as fee
+    pub deposit_fee: Decimal,
+    /// Share of redeemed base tokens taken as fee
+    pub withdrawal_fee: Decimal,
+    /// Share of yield taken as fee
+    pub performance_fee: Decimal,
+    /// Annual share of the vault taken as fee, accrued by minting strategy
+    /// tokens to the fee recipient
+    pub management_fee: Decimal,
+    /// Address fees are paid to, required if any fee is set
+    pub fee_recipient: Option<Addr>,
+    /// Where deposited base tokens are deployed, held on the contract if
+    /// `None`
+    pub strategy: Option<Strategy>,
+    /// Swap routes `Harvest` sells reward tokens along
+    pub reward_routes: Vec<RewardRoute>,
+    /// Seconds a proposed config change waits before it can be executed
+    pub config_timelock: u64,
+    /// Seconds after which a pause lapses and users can redeem, unlock and
+    /// withdraw again, pauses last until unpaused if `None`
+    pub max_pause_duration: Option<u64>,
+    /// Minimum seconds between share price snapshots, zero snapshots every
+    /// deposit, redeem, unlock, harvest, rebalance and fee accrual
+    pub share_price_snapshot_interval: u64,
     pub strategy_denom: Option<String>,
     pub base_denom: String,
     pub test: String,
@@ -33,14 +67,277 @@ impl Configure for MyConfig {
 
         let config = Self {
             // Initialize fields from `instantiate_msg`
-            strategy_cap: Uint128::zero(),
+            strategy_cap: instantiate_msg.strategy_cap,
+            lockup_duration: instantiate_msg.lockup_duration.unwrap_or(Duration::Time(0)),
+            deposit_fee: instantiate_msg.deposit_fee.unwrap_or_default(),
+            withdrawal_fee: instantiate_msg.withdrawal_fee.unwrap_or_default(),
+            performance_fee: instantiate_msg.performance_fee.unwrap_or_default(),
+            management_fee: instantiate_msg.management_fee.unwrap_or_default(),
+            fee_recipient: instantiate_msg
+                .fee_recipient
+                .map(|fee_recipient| deps.api.addr_validate(&fee_recipient))
+                .transpose()?,
+            strategy: instantiate_msg
+                .strategy
+                .map(|msg| Strategy::new(deps.as_ref(), msg, &instantiate_msg.base_denom))
+                .transpose()?,
+            reward_routes: instantiate_msg.reward_routes.unwrap_or_default(),
+            config_timelock: instantiate_msg.config_timelock.unwrap_or_default(),
+            max_pause_duration: instantiate_msg.max_pause_duration,
+            share_price_snapshot_interval: instantiate_msg
+                .share_price_snapshot_interval
+                .unwrap_or_default(),
             strategy_denom: None,
             base_denom: instantiate_msg.base_denom.clone(),
             test: "hello".to_string(),
         };
 
+        validate_strategy_cap(config.strategy_cap)?;
+        config.validate_fees()?;
+        validate_reward_routes(&config.reward_routes, &config)?;
+        validate_config_timelock(config.config_timelock)?;
         config.save_to_storage(deps)?;
 
         Ok(config)
     }
 }
+
+impl MyConfig {
+    pub fn has_lockup(&self) -> bool {
+        !matches!(
+            self.lockup_duration,
+            Duration::Time(0) | Duration::Height(0)
+        )
+    }
+
+    pub fn validate_fees(&self) -> Result<(), MyContractError> {
+        for (name, fee, max) in [
+            ("deposit_fee", self.deposit_fee, MAX_DEPOSIT_FEE),
+            ("withdrawal_fee", self.withdrawal_fee, MAX_WITHDRAWAL_FEE),
+            ("performance_fee", self.performance_fee, MAX_PERFORMANCE_FEE),
+            ("management_fee", self.management_fee, MAX_MANAGEMENT_FEE),
+        ] {
+            ensure!(
+                fee <= max,
+                MyContractError::FeeTooHigh {
+                    name: name.to_string(),
+                    max
+                }
+            );
+        }
+
+        let has_fees = !(self.deposit_fee.is_zero()
+            && self.withdrawal_fee.is_zero()
+            && self.performance_fee.is_zero()
+            && self.management_fee.is_zero());
+        ensure!(
+            !has_fees || self.fee_recipient.is_some(),
+            MyContractError::MissingFeeRecipient {}
+        );
+
+        Ok(())
+    }
+
+    /// Whether `msg` only lowers the strategy cap or lengthens the config
+    /// timelock, which is allowed without waiting for the timelock.
+    pub fn is_tightening(&self, msg: &UpdateConfigMsg) -> bool {
+        let UpdateConfigMsg {
+            strategy_cap,
+            lockup_duration: None,
+            deposit_fee: None,
+            withdrawal_fee: None,
+            performance_fee: None,
+            management_fee: None,
+            fee_recipient: None,
+            reward_routes: None,
+            config_timelock,
+            max_pause_duration: None,
+            share_price_snapshot_interval: None,
+        } = msg
+        else {
+            return false;
+        };
+
+        let lowers_cap = match (strategy_cap, self.strategy_cap) {
+            (None, _) => true,
+            (Some(Some(new)), Some(old)) => *new <= old,
+            (Some(Some(_)), None) => true,
+            (Some(None), _) => false,
+        };
+        let lengthens_timelock =
+            config_timelock.map_or(true, |timelock| timelock >= self.config_timelock);
+
+        lowers_cap && lengthens_timelock
+    }
+
+    /// Applies a partial update, returning the old and new value of every
+    /// field that changed.
+    pub fn update(
+        &mut self,
+        api: &dyn Api,
+        msg: UpdateConfigMsg,
+    ) -> Result<Vec<Attribute>, MyContractError> {
+        let mut attributes = vec![];
+
+        if let Some(strategy_cap) = msg.strategy_cap {
+            validate_strategy_cap(strategy_cap)?;
+            if strategy_cap != self.strategy_cap {
+                attributes.extend(changed_attributes(
+                    "strategy_cap",
+                    display_option(self.strategy_cap),
+                    display_option(strategy_cap),
+                ));
+                self.strategy_cap = strategy_cap;
+            }
+        }
+
+        if let Some(lockup_duration) = msg.lockup_duration {
+            if lockup_duration != self.lockup_duration {
+                attributes.extend(changed_attributes(
+                    "lockup_duration",
+                    self.lockup_duration.to_string(),
+                    lockup_duration.to_string(),
+                ));
+                self.lockup_duration = lockup_duration;
+            }
+        }
+
+        for (name, field, value) in [
+            ("deposit_fee", &mut self.deposit_fee, msg.deposit_fee),
+            (
+                "withdrawal_fee",
+                &mut self.withdrawal_fee,
+                msg.withdrawal_fee,
+            ),
+            (
+                "performance_fee",
+                &mut self.performance_fee,
+                msg.performance_fee,
+            ),
+            (
+                "management_fee",
+                &mut self.management_fee,
+                msg.management_fee,
+            ),
+        ] {
+            if let Some(value) = value {
+                if value != *field {
+                    attributes.extend(changed_attributes(
+                        name,
+                        field.to_string(),
+                        value.to_string(),
+                    ));
+                    *field = value;
+                }
+            }
+        }
+
+        if let Some(fee_recipient) = msg.fee_recipient {
+            let fee_recipient = fee_recipient
+                .map(|fee_recipient| api.addr_validate(&fee_recipient))
+                .transpose()?;
+            if fee_recipient != self.fee_recipient {
+                attributes.extend(changed_attributes(
+                    "fee_recipient",
+                    display_option(self.fee_recipient.as_ref()),
+                    display_option(fee_recipient.as_ref()),
+                ));
+                self.fee_recipient = fee_recipient;
+            }
+        }
+
+        if let Some(reward_routes) = msg.reward_routes {
+            validate_reward_routes(&reward_routes, self)?;
+            if reward_routes != self.reward_routes {
+                attributes.extend(changed_attributes(
+                    "reward_routes",
+                    display_routes(&self.reward_routes),
+                    display_routes(&reward_routes),
+                ));
+                self.reward_routes = reward_routes;
+            }
+        }
+
+        if let Some(max_pause_duration) = msg.max_pause_duration {
+            if max_pause_duration != self.max_pause_duration {
+                attributes.extend(changed_attributes(
+                    "max_pause_duration",
+                    display_option(self.max_pause_duration),
+                    display_option(max_pause_duration),
+                ));
+                self.max_pause_duration = max_pause_duration;
+            }
+        }
+
+        if let Some(interval) = msg.share_price_snapshot_interval {
+            if interval != self.share_price_snapshot_interval {
+                attributes.extend(changed_attributes(
+                    "share_price_snapshot_interval",
+                    self.share_price_snapshot_interval.to_string(),
+                    interval.to_string(),
+                ));
+                self.share_price_snapshot_interval = interval;
+            }
+        }
+
+        if let Some(config_timelock) = msg.config_timelock {
+            validate_config_timelock(config_timelock)?;
+            if config_timelock != self.config_timelock {
+                attributes.extend(changed_attributes(
+                    "config_timelock",
+                    self.config_timelock.to_string(),
+                    config_timelock.to_string(),
+                ));
+                self.config_timelock = config_timelock;
+            }
+        }
+
+        self.validate_fees()?;
+
+        Ok(attributes)
+    }
+}
+
+/// A zero cap would reject every deposit, `None` removes the cap instead.
+fn validate_strategy_cap(strategy_cap: Option<Uint128>) -> Result<(), MyContractError> {
+    ensure!(
+        strategy_cap != Some(Uint128::zero()),
+        MyContractError::InvalidStrategyCap {}
+    );
+
+    Ok(())
+}
+
+fn validate_config_timelock(config_timelock: u64) -> Result<(), MyContractError> {
+    ensure!(
+        config_timelock <= MAX_CONFIG_TIMELOCK,
+        MyContractError::InvalidConfigTimelock {
+            max: MAX_CONFIG_TIMELOCK
+        }
+    );
+
+    Ok(())
+}
+
+fn changed_attributes(field: &str, old: String, new: String) -> [Attribute; 2] {
+    [
+        attr(format!("old_{}", field), old),
+        attr(format!("new_{}", field), new),
+    ]
+}
+
+fn display_routes(routes: &[RewardRoute]) -> String {
+    if routes.is_empty() {
+        return "none".to_string();
+    }
+
+    routes
+        .iter()
+        .map(|route| route.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn display_option<T: ToString>(value: Option<T>) -> String {
+    value.map_or("none".to_string(), |value| value.to_string())
+}
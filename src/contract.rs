@@ -1,34 +1,50 @@
 use crate::config::MyConfig;
-use crate::msg::InstantiateMsg;
+use crate::errors::ContractError;
+use crate::msg::{
+    to_vaultenator_execute_msg, to_vaultenator_query_msg, ExecuteMsg, ExtensionExecuteMsg,
+    ExtensionQueryMsg, InstantiateMsg, QueryMsg,
+};
 use crate::state::MyState;
 use cosmwasm_std::{
     entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
 };
 
 use vaultenator::{
-    admin::Administer,
     contract::Vaultenator,
-    errors::ContractError,
-    msg::{ExecuteMsg, MigrateMsg, QueryMsg},
+    handlers::Handle,
+    msg::{MigrateMsg, VaultenatorExtensionExecuteMsg},
     ownership::Own,
     query::Query,
-    reply::ReplyHandler,
 };
 
 pub struct MyVault;
 
 // # Custom trait implementations
 //
+// - Administer implemented in src/admin.rs.
 // - Configure implemented in src/config.rs.
 // - Describe implemented in src/describe.rs
 // - ManageState implemented in src/state.rs.
 // - Handle implemented in src/handle.rs.
+// - ReplyHandler implemented in src/reply.rs.
+//
+// Vault specific messages implemented in src/handle.rs and queries in
+// src/query.rs.
+//
+// Lockup extension implemented in src/lockup.rs and force unlock extension in
+// src/force_unlock.rs.
+//
+// Strategies deploying deposits implemented in src/strategy.rs, with the GAMM
+// pool strategy in src/gamm.rs and the concentrated liquidity strategy in
+// src/concentrated_liquidity.rs. Harvesting strategy rewards implemented in
+// src/harvest.rs. Roles granted by the owner implemented in src/roles.rs and
+// timelocked config changes in src/timelock.rs. Pause history recorded in
+// src/pause.rs, per-user deposit and withdrawal totals and depositors in
+// src/positions.rs and share price snapshots in src/share_price.rs.
 
 // Default implementations taken from Vaultenator crate
 impl Own for MyVault {}
-impl Administer<MyState> for MyVault {}
 impl Query<MyConfig, MyState> for MyVault {}
-impl ReplyHandler<MyConfig> for MyVault {}
 impl Vaultenator<MyConfig, MyState> for MyVault {}
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -38,7 +54,7 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    MyVault.instantiate(deps, env, info, msg)
+    Ok(MyVault.instantiate(deps, env, info, msg)?)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -48,20 +64,66 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    MyVault.execute(deps, env, info, msg)
+    match msg {
+        // Vaultenator ignores the amount and recipient on deposits and redemptions
+        ExecuteMsg::Deposit { amount, recipient } => {
+            MyVault.execute_deposit(deps, env, info, amount, recipient)
+        }
+        ExecuteMsg::Redeem { recipient, amount } => {
+            MyVault.execute_redeem(deps, env, info, amount, recipient)
+        }
+        ExecuteMsg::VaultExtension(ExtensionExecuteMsg::Lockup(msg)) => {
+            MyVault.execute_lockup(deps, env, info, msg)
+        }
+        ExecuteMsg::VaultExtension(ExtensionExecuteMsg::ForceUnlock(msg)) => {
+            MyVault.execute_force_unlock(deps, env, info, msg)
+        }
+        ExecuteMsg::VaultExtension(ExtensionExecuteMsg::MyVault(msg)) => {
+            MyVault.execute_extension(deps, env, info, msg)
+        }
+        // Recording pause history needs the block time
+        ExecuteMsg::VaultExtension(ExtensionExecuteMsg::Vaultenator(
+            VaultenatorExtensionExecuteMsg::SetOpen {},
+        )) => MyVault.execute_open(deps, env, info),
+        ExecuteMsg::VaultExtension(ExtensionExecuteMsg::Vaultenator(
+            VaultenatorExtensionExecuteMsg::Pause {},
+        )) => MyVault.execute_pause(deps, env, info, None),
+        ExecuteMsg::VaultExtension(ExtensionExecuteMsg::Vaultenator(
+            VaultenatorExtensionExecuteMsg::UnPause {},
+        )) => MyVault.execute_unpause(deps, env, info, None),
+        // Vaultenator leaves UpdateConfig unimplemented
+        ExecuteMsg::VaultExtension(ExtensionExecuteMsg::Vaultenator(
+            VaultenatorExtensionExecuteMsg::UpdateConfig {},
+        )) => Ok(MyVault.handle_update_config(deps, info)?),
+        msg => Ok(MyVault.execute(deps, env, info, to_vaultenator_execute_msg(msg)?)?),
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    MyVault.query(deps, env, msg)
+    match msg {
+        QueryMsg::VaultExtension(ExtensionQueryMsg::Lockup(msg)) => MyVault.query_lockup(deps, msg),
+        QueryMsg::VaultExtension(ExtensionQueryMsg::MyVault(msg)) => {
+            MyVault.query_extension(deps, env, msg)
+        }
+        // Vaultenator leaves the vault standard queries unimplemented
+        msg @ (QueryMsg::Info {}
+        | QueryMsg::PreviewDeposit { .. }
+        | QueryMsg::PreviewRedeem { .. }
+        | QueryMsg::TotalAssets {}
+        | QueryMsg::TotalVaultTokenSupply {}
+        | QueryMsg::ConvertToShares { .. }
+        | QueryMsg::ConvertToAssets { .. }) => MyVault.query_vault_standard(deps, env, msg),
+        msg => MyVault.query(deps, env, to_vaultenator_query_msg(msg)?),
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
-    MyVault.reply(deps, env, msg)
+    Ok(MyVault.reply(deps, env, msg)?)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
-    MyVault.migrate(deps, env, msg)
+    Ok(MyVault.migrate(deps, env, msg)?)
 }
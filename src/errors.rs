@@ -0,0 +1,92 @@
+use cosmwasm_std::{Decimal, StdError, Timestamp, Uint128};
+use cw_controllers::AdminError;
+use thiserror::Error;
+use vaultenator::errors::ContractError as VaultenatorError;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Vaultenator(#[from] VaultenatorError),
+
+    #[error("Config change {id} cannot be executed before {executable_at}")]
+    ConfigChangeNotReady { id: u64, executable_at: Timestamp },
+
+    #[error("Config change {id} not found")]
+    ConfigChangeNotFound { id: u64 },
+
+    #[error("Config changes are timelocked, use ProposeConfigChange")]
+    ConfigChangeTimelocked {},
+
+    #[error("Invalid {name}, must not exceed {max}")]
+    FeeTooHigh { name: String, max: Decimal },
+
+    #[error("Amount {amount} does not match the {funds} sent")]
+    FundsMismatch { amount: Uint128, funds: Uint128 },
+
+    #[error("Pool {pool_id} must pair the base denom with one other asset")]
+    InvalidPool { pool_id: u64 },
+
+    #[error("Invalid config timelock, must not exceed {max} seconds")]
+    InvalidConfigTimelock { max: u64 },
+
+    #[error("Invalid max slippage, must be less than one")]
+    InvalidMaxSlippage {},
+
+    #[error("Invalid amount for unlocking position {lockup_id}")]
+    InvalidUnlockingAmount { lockup_id: u64 },
+
+    #[error("Invalid strategy cap, must be greater than zero")]
+    InvalidStrategyCap {},
+
+    #[error("Invalid reward route for {denom}, must sell a reward denom once for the base denom")]
+    InvalidRewardRoute { denom: String },
+
+    #[error("Invalid tick range {lower_tick} to {upper_tick}, ticks must be ascending multiples of the tick spacing between the minimum and maximum ticks")]
+    InvalidTickRange { lower_tick: i64, upper_tick: i64 },
+
+    #[error("Invalid TWAP window, must be greater than zero seconds")]
+    InvalidTwapWindow {},
+
+    #[error("Vault has a lockup, use Unlock and WithdrawUnlocked to redeem")]
+    LockupEnabled {},
+
+    #[error("Fee recipient must be set when fees are charged")]
+    MissingFeeRecipient {},
+
+    #[error("Vault has no concentrated liquidity strategy to rebalance")]
+    NoConcentratedLiquidityStrategy {},
+
+    #[error("Pool {pool_id} must weight both assets equally")]
+    UnequalPoolWeights { pool_id: u64 },
+
+    #[error("Unlocking position {lockup_id} has not finished unlocking")]
+    UnlockingPositionLocked { lockup_id: u64 },
+
+    #[error("Unlocking position {lockup_id} not found")]
+    UnlockingPositionNotFound { lockup_id: u64 },
+
+    #[error("Pause reason too long, must not exceed {max} bytes")]
+    PauseReasonTooLong { max: usize },
+
+    #[error("Deposit too small, would mint zero shares")]
+    ZeroShares {},
+}
+
+// Vaultenator trait handlers return their own error type so vault specific
+// errors are carried through as a generic error with the same message
+impl From<ContractError> for VaultenatorError {
+    fn from(err: ContractError) -> Self {
+        match err {
+            ContractError::Admin(err) => VaultenatorError::Admin(err),
+            ContractError::Std(err) => VaultenatorError::Std(err),
+            ContractError::Vaultenator(err) => err,
+            err => VaultenatorError::Std(StdError::generic_err(err.to_string())),
+        }
+    }
+}
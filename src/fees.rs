@@ -0,0 +1,226 @@
+use crate::config::MyConfig;
+use crate::pricing::{share_price, VIRTUAL_ASSETS, VIRTUAL_SHARES};
+use crate::share_price::record_share_price;
+use crate::state::MyState;
+use cosmwasm_std::{
+    coin, Addr, BankMsg, Decimal, DepsMut, Env, Event, Response, StdError, StdResult, Timestamp,
+    Uint128,
+};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgMint;
+use vaultenator::errors::ContractError;
+use vaultenator::state::ManageState;
+
+pub const MAX_DEPOSIT_FEE: Decimal = Decimal::percent(5);
+pub const MAX_WITHDRAWAL_FEE: Decimal = Decimal::percent(5);
+pub const MAX_PERFORMANCE_FEE: Decimal = Decimal::percent(50);
+pub const MAX_MANAGEMENT_FEE: Decimal = Decimal::percent(5);
+
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Splits `amount` into the fee taken at `rate`, rounded down, and the
+/// remainder.
+pub fn split_fee(amount: Uint128, rate: Decimal) -> (Uint128, Uint128) {
+    let fee = amount.mul_floor(rate);
+    (fee, amount - fee)
+}
+
+fn fee_recipient(config: &MyConfig) -> StdResult<&Addr> {
+    config
+        .fee_recipient
+        .as_ref()
+        .ok_or_else(|| StdError::generic_err("Fee recipient not set"))
+}
+
+fn fee_event(fee_type: &str, recipient: &Addr, amount: Uint128, denom: &str) -> Event {
+    Event::new("fee").add_attributes(vec![
+        ("fee_type", fee_type.to_string()),
+        ("recipient", recipient.to_string()),
+        ("amount", amount.to_string()),
+        ("denom", denom.to_string()),
+    ])
+}
+
+/// Pays a fee of `amount` base tokens to the fee recipient and emits a
+/// `fee` event, leaving the response untouched if there is no fee.
+pub fn charge_fee(
+    response: Response,
+    config: &MyConfig,
+    fee_type: &str,
+    amount: Uint128,
+) -> StdResult<Response> {
+    if amount.is_zero() {
+        return Ok(response);
+    }
+
+    let fee_recipient = fee_recipient(config)?;
+
+    let msg_transfer = BankMsg::Send {
+        to_address: fee_recipient.to_string(),
+        amount: vec![coin(amount.into(), &config.base_denom)],
+    };
+
+    Ok(response.add_message(msg_transfer).add_event(fee_event(
+        fee_type,
+        fee_recipient,
+        amount,
+        &config.base_denom,
+    )))
+}
+
+/// Strategy tokens owed to the fee recipient for the management fee accrued
+/// since `last_fee_accrual`. The fee dilutes holders by the annual rate pro
+/// rata over the elapsed time.
+pub fn pending_management_fee(
+    config: &MyConfig,
+    last_fee_accrual: Timestamp,
+    total_supply: Uint128,
+    now: Timestamp,
+) -> Uint128 {
+    let elapsed = now.seconds().saturating_sub(last_fee_accrual.seconds());
+
+    total_supply.mul_floor(config.management_fee * Decimal::from_ratio(elapsed, SECONDS_PER_YEAR))
+}
+
+/// Strategy tokens owed to the fee recipient for the performance fee on the
+/// gain in share price above `high_water_mark`, with the mark the share price
+/// is reset to once they are minted. Nothing is owed below the mark.
+pub fn pending_performance_fee(
+    config: &MyConfig,
+    high_water_mark: Decimal,
+    total_assets: Uint128,
+    total_supply: Uint128,
+) -> (Uint128, Decimal) {
+    let price = share_price(total_assets, total_supply);
+    if price <= high_water_mark {
+        return (Uint128::zero(), high_water_mark);
+    }
+
+    let fee_assets = total_supply.mul_floor((price - high_water_mark) * config.performance_fee);
+
+    // Mint enough shares to be worth the fee after they dilute existing holders
+    let shares = fee_assets.multiply_ratio(
+        total_supply + VIRTUAL_SHARES,
+        total_assets + VIRTUAL_ASSETS - fee_assets,
+    );
+
+    (shares, share_price(total_assets, total_supply + shares))
+}
+
+/// Strategy tokens minted to the fee recipient by `accrue_fees`.
+pub struct AccruedFees {
+    pub management_fee: Uint128,
+    pub performance_fee: Uint128,
+}
+
+impl AccruedFees {
+    pub fn total(&self) -> Uint128 {
+        self.management_fee + self.performance_fee
+    }
+}
+
+/// Fees `accrue_fees` would mint at `now` and the high water mark it would
+/// reset to, without updating the state.
+pub fn pending_fees(
+    config: &MyConfig,
+    state: &MyState,
+    total_assets: Uint128,
+    total_supply: Uint128,
+    now: Timestamp,
+) -> (AccruedFees, Decimal) {
+    let management_fee = pending_management_fee(config, state.last_fee_accrual, total_supply, now);
+    let (performance_fee, high_water_mark) = pending_performance_fee(
+        config,
+        state.high_water_mark,
+        total_assets,
+        total_supply + management_fee,
+    );
+
+    (
+        AccruedFees {
+            management_fee,
+            performance_fee,
+        },
+        high_water_mark,
+    )
+}
+
+/// Accrues the management fee up to the current block and crystallises the
+/// performance fee, returning the strategy tokens to mint to the fee
+/// recipient with `mint_accrued_fees`.
+///
+/// Callers pricing shares in the same message must add the accrued total to
+/// the total supply as the mint only executes after the handler returns.
+pub fn accrue_fees(
+    deps: &mut DepsMut,
+    env: &Env,
+    config: &MyConfig,
+    total_assets: Uint128,
+    total_supply: Uint128,
+) -> Result<AccruedFees, ContractError> {
+    let mut state = MyState::get_from_storage(deps.as_ref())?;
+
+    let (fees, high_water_mark) =
+        pending_fees(config, &state, total_assets, total_supply, env.block.time);
+
+    state.last_fee_accrual = env.block.time;
+    state.high_water_mark = high_water_mark;
+    state.save_to_storage(deps)?;
+
+    record_share_price(
+        deps.storage,
+        env,
+        config,
+        total_assets,
+        total_supply + fees.total(),
+    )?;
+
+    Ok(fees)
+}
+
+/// Mints the fees returned by `accrue_fees` to the fee recipient.
+pub fn mint_accrued_fees(
+    response: Response,
+    env: &Env,
+    config: &MyConfig,
+    fees: &AccruedFees,
+) -> Result<Response, ContractError> {
+    let response = mint_fee_shares(response, env, config, "management", fees.management_fee)?;
+    mint_fee_shares(response, env, config, "performance", fees.performance_fee)
+}
+
+/// Mints `shares` strategy tokens to the fee recipient and emits a `fee`
+/// event, leaving the response untouched if there are no shares.
+fn mint_fee_shares(
+    response: Response,
+    env: &Env,
+    config: &MyConfig,
+    fee_type: &str,
+    shares: Uint128,
+) -> Result<Response, ContractError> {
+    if shares.is_zero() {
+        return Ok(response);
+    }
+
+    let fee_recipient = fee_recipient(config)?;
+    let strategy_denom = config
+        .strategy_denom
+        .as_ref()
+        .ok_or(ContractError::DenomNotInitialized {})?;
+
+    let msg = MsgMint {
+        sender: env.contract.address.to_string(),
+        amount: Some(OsmosisCoin {
+            denom: strategy_denom.to_string(),
+            amount: shares.to_string(),
+        }),
+        mint_to_address: fee_recipient.to_string(),
+    };
+
+    Ok(response.add_message(msg).add_event(fee_event(
+        fee_type,
+        fee_recipient,
+        shares,
+        strategy_denom,
+    )))
+}
@@ -0,0 +1,150 @@
+use crate::config::MyConfig;
+use crate::contract::MyVault;
+use crate::errors::ContractError;
+use crate::lockup::{release_unlocking, unlocking_positions};
+use crate::state::MyState;
+use cosmwasm_std::{
+    coin, ensure, Addr, BankMsg, Deps, DepsMut, Empty, Env, Event, MessageInfo, Response,
+    StdResult, Uint128,
+};
+use cw_storage_plus::Map;
+use cw_vault_standard::extensions::force_unlock::ForceUnlockExecuteMsg;
+use vaultenator::config::Configure;
+use vaultenator::errors::ContractError as VaultenatorError;
+use vaultenator::state::OWNER;
+
+/// Addresses allowed to bypass the lockup, such as liquidators
+pub const FORCE_WITHDRAW_WHITELIST: Map<&Addr, Empty> = Map::new("force_withdraw_whitelist");
+
+pub fn is_whitelisted(deps: Deps, address: &Addr) -> StdResult<bool> {
+    Ok(FORCE_WITHDRAW_WHITELIST.has(deps.storage, address))
+}
+
+impl MyVault {
+    pub fn execute_force_unlock(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ForceUnlockExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        match msg {
+            ForceUnlockExecuteMsg::ForceRedeem { recipient, amount } => {
+                self.force_redeem(deps, env, info, amount, recipient)
+            }
+            ForceUnlockExecuteMsg::ForceWithdrawUnlocking {
+                lockup_id,
+                amount,
+                recipient,
+            } => self.force_withdraw_unlocking(deps, env, info, lockup_id, amount, recipient),
+            ForceUnlockExecuteMsg::UpdateForceWithdrawWhitelist {
+                add_addresses,
+                remove_addresses,
+            } => self.update_force_withdraw_whitelist(deps, info, add_addresses, remove_addresses),
+        }
+    }
+
+    /// Redeems vault tokens immediately regardless of the lockup.
+    fn force_redeem(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+        recipient: Option<String>,
+    ) -> Result<Response, ContractError> {
+        MyState::assert_can_withdraw(deps.as_ref(), &env)?;
+        ensure!(
+            is_whitelisted(deps.as_ref(), &info.sender)?,
+            VaultenatorError::Unauthorized {}
+        );
+
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+
+        let recipient = match recipient {
+            Some(recipient) => deps.api.addr_validate(&recipient)?,
+            None => info.sender.clone(),
+        };
+
+        let response = self.redeem(deps, env, info, config, amount, recipient.clone())?;
+
+        Ok(response.add_event(Event::new("force_redeem").add_attribute("recipient", recipient)))
+    }
+
+    /// Withdraws base tokens from any unlocking position before it is
+    /// released.
+    fn force_withdraw_unlocking(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        lockup_id: u64,
+        amount: Option<Uint128>,
+        recipient: Option<String>,
+    ) -> Result<Response, ContractError> {
+        MyState::assert_can_withdraw(deps.as_ref(), &env)?;
+        ensure!(
+            is_whitelisted(deps.as_ref(), &info.sender)?,
+            VaultenatorError::Unauthorized {}
+        );
+
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+
+        let position = unlocking_positions()
+            .may_load(deps.storage, lockup_id)?
+            .ok_or(ContractError::UnlockingPositionNotFound { lockup_id })?;
+
+        let amount = amount.unwrap_or(position.base_token_amount);
+        ensure!(
+            !amount.is_zero() && amount <= position.base_token_amount,
+            ContractError::InvalidUnlockingAmount { lockup_id }
+        );
+
+        let recipient = match recipient {
+            Some(recipient) => deps.api.addr_validate(&recipient)?,
+            None => info.sender,
+        };
+
+        release_unlocking(deps.storage, position, amount)?;
+
+        let msg_transfer = BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.into(), config.base_denom)],
+        };
+
+        let event = Event::new("force_withdraw_unlocking").add_attributes(vec![
+            ("lockup_id", lockup_id.to_string()),
+            ("recipient", recipient.to_string()),
+            ("base_token_amount", amount.to_string()),
+        ]);
+
+        Ok(Response::new().add_message(msg_transfer).add_event(event))
+    }
+
+    fn update_force_withdraw_whitelist(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        add_addresses: Vec<String>,
+        remove_addresses: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        OWNER.assert_admin(deps.as_ref(), &info.sender)?;
+
+        for address in &add_addresses {
+            let address = deps.api.addr_validate(address)?;
+            FORCE_WITHDRAW_WHITELIST.save(deps.storage, &address, &Empty {})?;
+        }
+
+        for address in &remove_addresses {
+            let address = deps.api.addr_validate(address)?;
+            FORCE_WITHDRAW_WHITELIST.remove(deps.storage, &address);
+        }
+
+        let event = Event::new("update_force_withdraw_whitelist").add_attributes(vec![
+            ("added", add_addresses.join(",")),
+            ("removed", remove_addresses.join(",")),
+        ]);
+
+        Ok(Response::new().add_event(event))
+    }
+}
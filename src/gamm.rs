@@ -0,0 +1,411 @@
+use crate::config::MyConfig;
+use crate::errors::ContractError;
+use crate::pricing::VIRTUAL_SHARES;
+use crate::strategy::{
+    display_coins, finish_deposit, finish_withdrawal, min_amount_out, query_balance, twap_price,
+    validate_twap_params, PendingWithdrawal, PENDING_WITHDRAWAL,
+};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    ensure, Decimal, Deps, DepsMut, Env, Event, Isqrt, Reply, Response, StdError, StdResult,
+    SubMsg, Uint128, Uint256,
+};
+use cw_storage_plus::Item;
+use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
+use osmosis_std::types::osmosis::gamm::v1beta1::{
+    MsgExitPool, MsgExitPoolResponse, MsgJoinPool, MsgJoinPoolResponse, MsgSwapExactAmountIn,
+    MsgSwapExactAmountInResponse, Pool, QueryCalcJoinPoolNoSwapSharesRequest,
+};
+use osmosis_std::types::osmosis::poolmanager::v1beta1::{PoolRequest, SwapAmountInRoute};
+use std::str::FromStr;
+use vaultenator::errors::ContractError as VaultenatorError;
+
+pub const GAMM_INVEST_SWAP_REPLY_ID: u64 = 2;
+pub const GAMM_JOIN_REPLY_ID: u64 = 3;
+pub const GAMM_EXIT_REPLY_ID: u64 = 4;
+pub const GAMM_DIVEST_SWAP_REPLY_ID: u64 = 5;
+
+/// Base tokens waiting to join the pool with the output of the invest swap
+pub const PENDING_JOIN: Item<Uint128> = Item::new("gamm_pending_join");
+
+/// Provides liquidity to a two asset balancer pool weighting `base_denom`
+/// and `pair_denom` equally.
+#[cw_serde]
+pub struct GammStrategy {
+    pub pool_id: u64,
+    pub pair_denom: String,
+    /// Seconds pair tokens are priced over
+    pub twap_window: u64,
+    /// Share of the output the TWAP implies swaps may lose
+    pub max_slippage: Decimal,
+}
+
+impl GammStrategy {
+    /// Checks the pool is a balancer pool weighting `base_denom` and exactly
+    /// one other asset equally, the only pools `value` can price.
+    pub fn new(
+        deps: Deps,
+        pool_id: u64,
+        base_denom: &str,
+        twap_window: u64,
+        max_slippage: Decimal,
+    ) -> Result<Self, ContractError> {
+        validate_twap_params(twap_window, max_slippage)?;
+
+        let pool = query_pool(deps, pool_id)?;
+        let denoms: Vec<&str> = pool
+            .pool_assets
+            .iter()
+            .filter_map(|asset| asset.token.as_ref())
+            .map(|token| token.denom.as_str())
+            .collect();
+
+        ensure!(
+            pool.pool_assets.len() == 2 && denoms.len() == 2 && denoms.contains(&base_denom),
+            ContractError::InvalidPool { pool_id }
+        );
+        ensure!(
+            pool.pool_assets[0].weight == pool.pool_assets[1].weight,
+            ContractError::UnequalPoolWeights { pool_id }
+        );
+
+        let pair_denom = denoms
+            .into_iter()
+            .find(|denom| *denom != base_denom)
+            .ok_or(ContractError::InvalidPool { pool_id })?
+            .to_string();
+
+        Ok(Self {
+            pool_id,
+            pair_denom,
+            twap_window,
+            max_slippage,
+        })
+    }
+
+    pub fn lp_denom(&self) -> String {
+        format!("gamm/pool/{}", self.pool_id)
+    }
+
+    /// Value in base tokens of the LP shares and pair tokens held by the
+    /// vault. Pair tokens are valued at their TWAP and LP shares at the
+    /// reserves the pool would hold at that price, so swapping against the
+    /// pool cannot move the value within a block.
+    pub fn value(&self, deps: Deps, env: &Env, base_denom: &str) -> Result<Uint128, ContractError> {
+        let lp_shares = query_balance(deps, env, &self.lp_denom())?;
+        let pair_amount = query_balance(deps, env, &self.pair_denom)?;
+
+        if lp_shares.is_zero() && pair_amount.is_zero() {
+            return Ok(Uint128::zero());
+        }
+
+        let pair_price = self.twap_price(deps, env, &self.pair_denom, base_denom)?;
+        let mut value = pair_amount.mul_floor(pair_price);
+
+        if !lp_shares.is_zero() {
+            value += self.lp_value(deps, base_denom, pair_price, lp_shares)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Swaps half of `amount` base tokens for pair tokens, joining the pool
+    /// with both once the swap replies.
+    pub fn invest(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        base_denom: &str,
+        amount: Uint128,
+    ) -> StdResult<Vec<SubMsg>> {
+        let swap_amount = amount.multiply_ratio(1u128, 2u128);
+        if swap_amount.is_zero() {
+            return Ok(vec![]);
+        }
+
+        PENDING_JOIN.save(deps.storage, &(amount - swap_amount))?;
+
+        let msg = self.swap_msg(
+            deps.as_ref(),
+            env,
+            base_denom,
+            &self.pair_denom,
+            swap_amount,
+        )?;
+
+        Ok(vec![SubMsg::reply_on_success(
+            msg,
+            GAMM_INVEST_SWAP_REPLY_ID,
+        )])
+    }
+
+    /// Exits the pool with the share of LP shares owned by `shares` strategy
+    /// tokens and swaps the pair tokens received back to base tokens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw(
+        &self,
+        response: Response,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        mut withdrawal: PendingWithdrawal,
+        shares: Uint128,
+        total_supply: Uint128,
+    ) -> Result<Response, ContractError> {
+        let lp_shares = query_balance(deps.as_ref(), env, &self.lp_denom())?
+            .multiply_ratio(shares, total_supply + VIRTUAL_SHARES);
+        withdrawal.pair_amount = query_balance(deps.as_ref(), env, &self.pair_denom)?
+            .multiply_ratio(shares, total_supply + VIRTUAL_SHARES);
+
+        if lp_shares.is_zero() {
+            return self.swap_to_base(response, deps, env, config, withdrawal);
+        }
+
+        PENDING_WITHDRAWAL.save(deps.storage, &withdrawal)?;
+
+        let msg = MsgExitPool {
+            sender: env.contract.address.to_string(),
+            pool_id: self.pool_id,
+            share_in_amount: lp_shares.to_string(),
+            token_out_mins: vec![],
+        };
+
+        Ok(response.add_submessage(SubMsg::reply_on_success(msg, GAMM_EXIT_REPLY_ID)))
+    }
+
+    pub fn handle_reply(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        match msg.id {
+            GAMM_INVEST_SWAP_REPLY_ID => {
+                let response: MsgSwapExactAmountInResponse = msg.result.try_into()?;
+                self.join_pool(deps, env, config, response)
+            }
+            GAMM_JOIN_REPLY_ID => {
+                let response: MsgJoinPoolResponse = msg.result.try_into()?;
+
+                let event = Event::new("join_pool").add_attributes(vec![
+                    ("pool_id", self.pool_id.to_string()),
+                    ("share_out_amount", response.share_out_amount),
+                    ("token_in", display_coins(&response.token_in)),
+                ]);
+
+                finish_deposit(Response::new().add_event(event), deps, env, config)
+            }
+            GAMM_EXIT_REPLY_ID => {
+                let response: MsgExitPoolResponse = msg.result.try_into()?;
+
+                let mut withdrawal = PENDING_WITHDRAWAL.load(deps.storage)?;
+                for token in &response.token_out {
+                    let amount = Uint128::from_str(&token.amount)?;
+                    if token.denom == config.base_denom {
+                        withdrawal.base_amount += amount;
+                    } else if token.denom == self.pair_denom {
+                        withdrawal.pair_amount += amount;
+                    }
+                }
+
+                let event = Event::new("exit_pool").add_attributes(vec![
+                    ("pool_id", self.pool_id.to_string()),
+                    ("token_out", display_coins(&response.token_out)),
+                ]);
+
+                self.swap_to_base(
+                    Response::new().add_event(event),
+                    deps,
+                    env,
+                    config,
+                    withdrawal,
+                )
+            }
+            GAMM_DIVEST_SWAP_REPLY_ID => {
+                let response: MsgSwapExactAmountInResponse = msg.result.try_into()?;
+
+                let mut withdrawal = PENDING_WITHDRAWAL.load(deps.storage)?;
+                PENDING_WITHDRAWAL.remove(deps.storage);
+
+                withdrawal.base_amount += Uint128::from_str(&response.token_out_amount)?;
+                withdrawal.pair_amount = Uint128::zero();
+
+                finish_withdrawal(Response::new(), deps, env, config, withdrawal)
+            }
+            _ => Err(VaultenatorError::InvalidReplyId.into()),
+        }
+    }
+
+    /// Joins the pool with the base tokens set aside by `invest` and the pair
+    /// tokens the swap returned, leftovers stay on the contract.
+    fn join_pool(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        response: MsgSwapExactAmountInResponse,
+    ) -> Result<Response, ContractError> {
+        let base_amount = PENDING_JOIN.load(deps.storage)?;
+        PENDING_JOIN.remove(deps.storage);
+
+        let pair_amount = Uint128::from_str(&response.token_out_amount)?;
+
+        // Coins must be sorted by denom and non-zero
+        let mut tokens_in: Vec<OsmosisCoin> = [
+            (&config.base_denom, base_amount),
+            (&self.pair_denom, pair_amount),
+        ]
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(denom, amount)| OsmosisCoin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        })
+        .collect();
+        tokens_in.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+        let shares_out = QueryCalcJoinPoolNoSwapSharesRequest {
+            pool_id: self.pool_id,
+            tokens_in: tokens_in.clone(),
+        }
+        .query(&deps.querier)?
+        .shares_out;
+
+        if Uint128::from_str(&shares_out)?.is_zero() {
+            return finish_deposit(Response::new(), deps, env, config);
+        }
+
+        let msg = MsgJoinPool {
+            sender: env.contract.address.to_string(),
+            pool_id: self.pool_id,
+            share_out_amount: shares_out,
+            token_in_maxs: tokens_in,
+        };
+
+        Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, GAMM_JOIN_REPLY_ID)))
+    }
+
+    /// Swaps the withdrawal's pair tokens to base tokens, finishing the
+    /// withdrawal straight away if there are none.
+    fn swap_to_base(
+        &self,
+        response: Response,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        withdrawal: PendingWithdrawal,
+    ) -> Result<Response, ContractError> {
+        if withdrawal.pair_amount.is_zero() {
+            PENDING_WITHDRAWAL.remove(deps.storage);
+            return finish_withdrawal(response, deps, env, config, withdrawal);
+        }
+
+        PENDING_WITHDRAWAL.save(deps.storage, &withdrawal)?;
+
+        let msg = self.swap_msg(
+            deps.as_ref(),
+            env,
+            &self.pair_denom,
+            &config.base_denom,
+            withdrawal.pair_amount,
+        )?;
+
+        Ok(response.add_submessage(SubMsg::reply_on_success(msg, GAMM_DIVEST_SWAP_REPLY_ID)))
+    }
+
+    /// Value of `lp_shares` at the reserves the pool would hold if it traded
+    /// at `pair_price`. Both assets of an equally weighted pool are then worth
+    /// `sqrt(base_reserve * pair_reserve * pair_price)`, and the product of
+    /// the reserves only grows as the pool is traded.
+    fn lp_value(
+        &self,
+        deps: Deps,
+        base_denom: &str,
+        pair_price: Decimal,
+        lp_shares: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        let pool = query_pool(deps, self.pool_id)?;
+
+        let mut base_reserve = Uint128::zero();
+        let mut pair_reserve = Uint128::zero();
+        for token in pool.pool_assets.into_iter().filter_map(|asset| asset.token) {
+            let amount = Uint128::from_str(&token.amount)?;
+            if token.denom == base_denom {
+                base_reserve = amount;
+            } else if token.denom == self.pair_denom {
+                pair_reserve = amount;
+            }
+        }
+
+        let total_shares = match pool.total_shares {
+            Some(total_shares) => Uint128::from_str(&total_shares.amount)?,
+            None => Uint128::zero(),
+        };
+        if total_shares.is_zero() {
+            return Ok(Uint128::zero());
+        }
+
+        let pool_value = base_reserve
+            .full_mul(pair_reserve.mul_floor(pair_price))
+            .isqrt()
+            * Uint256::from(2u128);
+
+        Ok(pool_value
+            .multiply_ratio(lp_shares, total_shares)
+            .try_into()
+            .map_err(StdError::from)?)
+    }
+
+    fn twap_price(
+        &self,
+        deps: Deps,
+        env: &Env,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> StdResult<Decimal> {
+        twap_price(
+            deps,
+            env,
+            self.pool_id,
+            base_asset,
+            quote_asset,
+            self.twap_window,
+        )
+    }
+
+    /// Swaps `amount` through the pool, reverting if it returns more than
+    /// `max_slippage` less than the TWAP implies.
+    fn swap_msg(
+        &self,
+        deps: Deps,
+        env: &Env,
+        denom_in: &str,
+        denom_out: &str,
+        amount: Uint128,
+    ) -> StdResult<MsgSwapExactAmountIn> {
+        let price = self.twap_price(deps, env, denom_in, denom_out)?;
+
+        Ok(MsgSwapExactAmountIn {
+            sender: env.contract.address.to_string(),
+            routes: vec![SwapAmountInRoute {
+                pool_id: self.pool_id,
+                token_out_denom: denom_out.to_string(),
+            }],
+            token_in: Some(OsmosisCoin {
+                denom: denom_in.to_string(),
+                amount: amount.to_string(),
+            }),
+            token_out_min_amount: min_amount_out(amount, price, self.max_slippage).to_string(),
+        })
+    }
+}
+
+fn query_pool(deps: Deps, pool_id: u64) -> Result<Pool, ContractError> {
+    PoolRequest { pool_id }
+        .query(&deps.querier)?
+        .pool
+        .filter(|pool| pool.type_url == Pool::TYPE_URL)
+        .and_then(|pool| Pool::try_from(pool).ok())
+        .ok_or(ContractError::InvalidPool { pool_id })
+}
@@ -1,11 +1,21 @@
 use crate::config::MyConfig;
 use crate::contract::MyVault;
+use crate::errors::ContractError as MyContractError;
+use crate::fees::{accrue_fees, charge_fee, mint_accrued_fees, split_fee};
+use crate::msg::{MyVaultExtensionExecuteMsg, UpdateConfigMsg};
+use crate::positions::record_deposit;
+use crate::pricing::{total_assets, total_supply};
+use crate::roles::{assert_any_role, assert_can_update_config, Role};
+use crate::share_price::record_current_share_price;
 use crate::state::MyState;
-use cosmwasm_std::{coin, BankMsg, CosmosMsg, DepsMut, Env, Event, MessageInfo, Response, SubMsg};
+use crate::strategy::{self, withdraw, PendingDeposit, Strategy, WithdrawalKind};
+use cosmwasm_std::{
+    ensure, Addr, DepsMut, Env, Event, MessageInfo, Response, StdError, SubMsg, Uint128,
+};
 use cw2::set_contract_version;
 use cw_utils::must_pay;
 use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
-use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgMint};
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgBurn;
 use serde::{de::DeserializeOwned, Serialize};
 use vaultenator::config::Configure;
 use vaultenator::contract::Describe;
@@ -46,63 +56,166 @@ impl Handle<MyConfig, MyState> for MyVault {
             .add_attribute("action", "instantiate"))
     }
 
+    // Vaultenator's UpdateConfig carries no payload, config is updated
+    // through the MyVault extension instead
     fn handle_update_config(
+        &self,
+        _deps: DepsMut,
+        _info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        Err(ContractError::InvalidMessage {})
+    }
+    fn handle_deposit(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
     ) -> Result<Response, ContractError> {
-        OWNER.assert_admin(deps.as_ref(), &info.sender)?;
-
-        // let config = MyConfig::get_from_storage(deps.as_ref())?;
-        // config.strategy_cap = new_config.strategy_cap;
-
-        // config.save_to_storage(&mut deps)?;
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+        let amount =
+            must_pay(&info, &config.base_denom).map_err(|_| ContractError::InvalidFunds {})?;
 
-        Ok(Response::new().add_event(Event::new("update_config")))
+        Ok(self.execute_deposit(deps, env, info, amount, None)?)
     }
-    fn handle_deposit(
+    fn handle_redeem(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
     ) -> Result<Response, ContractError> {
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+        let strategy_denom = config
+            .strategy_denom
+            .as_ref()
+            .ok_or(ContractError::DenomNotInitialized {})?;
+        let amount = must_pay(&info, strategy_denom).map_err(|_| ContractError::InvalidFunds {})?;
+
+        Ok(self.execute_redeem(deps, env, info, amount, None)?)
+    }
+}
+
+/// Checks that exactly `amount` of `denom` was sent with the message.
+pub(crate) fn must_pay_amount(
+    info: &MessageInfo,
+    denom: &str,
+    amount: Uint128,
+) -> Result<Uint128, MyContractError> {
+    let funds = must_pay(info, denom).map_err(|_| ContractError::InvalidFunds {})?;
+    ensure!(
+        funds == amount,
+        MyContractError::FundsMismatch { amount, funds }
+    );
+
+    Ok(funds)
+}
+
+impl MyVault {
+    /// Mints vault tokens for the deposited base tokens to `recipient`,
+    /// defaulting to the sender.
+    pub fn execute_deposit(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+        recipient: Option<String>,
+    ) -> Result<Response, MyContractError> {
         MyState::is_open_and_unpaused(deps.as_ref())?;
         let config = MyConfig::get_from_storage(deps.as_ref())?;
 
-        let amount =
-            must_pay(&info, &config.base_denom).map_err(|_| ContractError::InvalidFunds {})?;
+        let recipient = match recipient {
+            Some(recipient) => deps.api.addr_validate(&recipient)?,
+            None => info.sender.clone(),
+        };
+
+        let amount = must_pay_amount(&info, &config.base_denom, amount)?;
 
         let strategy_denom = config
             .strategy_denom
             .as_ref()
             .ok_or(ContractError::DenomNotInitialized {})?;
 
-        let msg = MsgMint {
-            sender: env.contract.address.to_string(),
-            amount: Some(OsmosisCoin {
-                denom: strategy_denom.to_string(),
-                amount: amount.to_string(),
-            }),
-            mint_to_address: info.sender.to_string(),
+        // Funds are transferred before execution so the deposit is already
+        // included in the contract balance
+        let total_assets = total_assets(deps.as_ref(), &env, &config)?
+            .checked_sub(amount)
+            .map_err(StdError::from)?;
+
+        record_deposit(deps.storage, &recipient, amount)?;
+
+        let (fee, amount) = split_fee(amount, config.deposit_fee);
+
+        if let Some(strategy_cap) = config.strategy_cap {
+            ensure!(
+                total_assets + amount <= strategy_cap,
+                ContractError::StrategyCapExceeded {}
+            );
+        }
+
+        let total_supply = total_supply(deps.as_ref(), strategy_denom)?;
+        let fees = accrue_fees(&mut deps, &env, &config, total_assets, total_supply)?;
+
+        // Fees are paid before the strategy deploys the deposit so they are
+        // not counted as value it added
+        let response = mint_accrued_fees(Response::default(), &env, &config, &fees)?;
+        let response = charge_fee(response, &config, "deposit", fee)?;
+
+        let deposit = PendingDeposit {
+            sender: info.sender,
+            recipient,
+            amount: amount + fee,
+            fee,
+            total_assets,
+            total_supply: total_supply + fees.total(),
         };
 
-        Ok(Response::default().add_message(msg))
+        strategy::deposit(response, deps, &env, &config, deposit)
     }
-    fn handle_redeem(
+
+    /// Redeems vault tokens for base tokens paid to `recipient`, defaulting to
+    /// the sender.
+    pub fn execute_redeem(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-    ) -> Result<Response, ContractError> {
-        MyState::is_open_and_unpaused(deps.as_ref())?;
+        amount: Uint128,
+        recipient: Option<String>,
+    ) -> Result<Response, MyContractError> {
+        MyState::assert_can_withdraw(deps.as_ref(), &env)?;
         let config = MyConfig::get_from_storage(deps.as_ref())?;
 
+        ensure!(!config.has_lockup(), MyContractError::LockupEnabled {});
+
+        let recipient = match recipient {
+            Some(recipient) => deps.api.addr_validate(&recipient)?,
+            None => info.sender.clone(),
+        };
+
+        self.redeem(deps, env, info, config, amount, recipient)
+    }
+
+    /// Burns the vault tokens sent and pays out the base tokens they are worth
+    /// at the current share price.
+    pub(crate) fn redeem(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        config: MyConfig,
+        amount: Uint128,
+        recipient: Addr,
+    ) -> Result<Response, MyContractError> {
         let strategy_denom = config
             .strategy_denom
+            .as_ref()
             .ok_or(ContractError::DenomNotInitialized {})?;
 
-        let strategy_denom_amount =
-            must_pay(&info, &strategy_denom).map_err(|_| ContractError::InvalidFunds {})?;
+        let strategy_denom_amount = must_pay_amount(&info, strategy_denom, amount)?;
+
+        let total_assets = total_assets(deps.as_ref(), &env, &config)?;
+        let total_supply = total_supply(deps.as_ref(), strategy_denom)?;
+        let fees = accrue_fees(&mut deps, &env, &config, total_assets, total_supply)?;
 
         let burn_strategy_token_msg = MsgBurn {
             sender: env.contract.address.to_string(),
@@ -113,17 +226,160 @@ impl Handle<MyConfig, MyState> for MyVault {
             burn_from_address: env.contract.address.to_string(),
         };
 
-        let msg_transfer = CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: vec![coin(strategy_denom_amount.into(), config.base_denom)],
-        });
+        let response = Response::default().add_message(burn_strategy_token_msg);
+        let response = mint_accrued_fees(response, &env, &config, &fees)?;
+
+        withdraw(
+            response,
+            deps,
+            &env,
+            &config,
+            WithdrawalKind::Redeem {
+                owner: info.sender,
+                recipient,
+            },
+            strategy_denom_amount,
+            total_assets,
+            total_supply + fees.total(),
+        )
+    }
 
-        let mut response = Response::default();
+    pub fn execute_extension(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: MyVaultExtensionExecuteMsg,
+    ) -> Result<Response, MyContractError> {
+        match msg {
+            MyVaultExtensionExecuteMsg::UpdateConfig(msg) => {
+                self.update_config(deps, env, info, msg)
+            }
+            MyVaultExtensionExecuteMsg::ProposeConfigChange(msg) => {
+                self.propose_config_change(deps, env, info, msg)
+            }
+            MyVaultExtensionExecuteMsg::ExecuteConfigChange { id } => {
+                self.execute_config_change(deps, env, info, id)
+            }
+            MyVaultExtensionExecuteMsg::CancelConfigChange { id } => {
+                self.cancel_config_change(deps, info, id)
+            }
+            MyVaultExtensionExecuteMsg::AccrueFees {} => {
+                let config = MyConfig::get_from_storage(deps.as_ref())?;
+                Self::settle_fees(&mut deps, &env, &config)
+            }
+            MyVaultExtensionExecuteMsg::Rebalance {
+                lower_tick,
+                upper_tick,
+            } => self.rebalance(deps, env, info, lower_tick, upper_tick),
+            MyVaultExtensionExecuteMsg::Harvest {} => self.harvest(deps, env, info),
+            MyVaultExtensionExecuteMsg::Pause { reason } => {
+                self.execute_pause(deps, env, info, reason)
+            }
+            MyVaultExtensionExecuteMsg::Unpause { reason } => {
+                self.execute_unpause(deps, env, info, reason)
+            }
+            MyVaultExtensionExecuteMsg::GrantRole { role, address } => {
+                self.grant_role(deps, info, role, address)
+            }
+            MyVaultExtensionExecuteMsg::RevokeRole { role, address } => {
+                self.revoke_role(deps, info, role, address)
+            }
+        }
+    }
+
+    fn update_config(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: UpdateConfigMsg,
+    ) -> Result<Response, MyContractError> {
+        assert_can_update_config(deps.as_ref(), &info.sender, &msg)?;
+
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+        ensure!(
+            config.config_timelock == 0 || config.is_tightening(&msg),
+            MyContractError::ConfigChangeTimelocked {}
+        );
+
+        Self::apply_config_update(deps, &env, msg)
+    }
+
+    /// Settles fees and applies `msg` to the config.
+    pub(crate) fn apply_config_update(
+        mut deps: DepsMut,
+        env: &Env,
+        msg: UpdateConfigMsg,
+    ) -> Result<Response, MyContractError> {
+        let mut config = MyConfig::get_from_storage(deps.as_ref())?;
+
+        // Settle fees at the old rates and recipient first
+        let response = Self::settle_fees(&mut deps, env, &config)?;
+
+        let attributes = config.update(deps.api, msg)?;
+
+        config.save_to_storage(&mut deps)?;
+
+        Ok(response.add_event(Event::new("update_config").add_attributes(attributes)))
+    }
+
+    /// Moves the concentrated liquidity strategy to a new tick range and
+    /// re-creates the vault's position in it.
+    fn rebalance(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        lower_tick: i64,
+        upper_tick: i64,
+    ) -> Result<Response, MyContractError> {
+        assert_any_role(
+            deps.as_ref(),
+            &info.sender,
+            &[Role::Strategist, Role::Keeper],
+        )?;
+        // Rebalancing re-invests the position, which stays blocked while the
+        // vault is paused
+        MyState::is_open_and_unpaused(deps.as_ref())?;
+
+        let mut config = MyConfig::get_from_storage(deps.as_ref())?;
+        record_current_share_price(deps.branch(), &env, &config)?;
+
+        let Some(Strategy::ConcentratedLiquidity(strategy)) = &mut config.strategy else {
+            return Err(MyContractError::NoConcentratedLiquidityStrategy {});
+        };
+
+        let event = Event::new("rebalance").add_attributes(vec![
+            ("old_lower_tick", strategy.lower_tick.to_string()),
+            ("old_upper_tick", strategy.upper_tick.to_string()),
+            ("new_lower_tick", lower_tick.to_string()),
+            ("new_upper_tick", upper_tick.to_string()),
+        ]);
+
+        strategy.set_range(deps.as_ref(), lower_tick, upper_tick)?;
+        let response = strategy.rebalance(deps.branch(), &env)?;
+
+        config.save_to_storage(&mut deps)?;
+
+        Ok(response.add_event(event))
+    }
+
+    /// Mints the management fee accrued since the last accrual and any
+    /// performance fee above the high water mark to the fee recipient.
+    fn settle_fees(
+        deps: &mut DepsMut,
+        env: &Env,
+        config: &MyConfig,
+    ) -> Result<Response, MyContractError> {
+        let total_assets = total_assets(deps.as_ref(), env, config)?;
+        let total_supply = match &config.strategy_denom {
+            Some(strategy_denom) => total_supply(deps.as_ref(), strategy_denom)?,
+            None => Uint128::zero(),
+        };
 
-        response = response
-            .add_message(burn_strategy_token_msg)
-            .add_message(msg_transfer);
+        let fees = accrue_fees(deps, env, config, total_assets, total_supply)?;
 
-        Ok(response)
+        Ok(mint_accrued_fees(Response::new(), env, config, &fees)?)
     }
 }
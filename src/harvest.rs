@@ -0,0 +1,346 @@
+use crate::config::MyConfig;
+use crate::contract::MyVault;
+use crate::errors::ContractError;
+use crate::roles::{assert_any_role, Role};
+use crate::share_price::record_current_share_price;
+use crate::state::MyState;
+use crate::strategy::{
+    display_coins, min_amount_out, query_balance, twap_price, Strategy, DEFAULT_MAX_SLIPPAGE,
+    DEFAULT_TWAP_WINDOW,
+};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    ensure, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Reply, Response, StdResult, SubMsg,
+    Uint128,
+};
+use cw_storage_plus::Item;
+use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
+use osmosis_std::types::osmosis::poolmanager::v1beta1::{
+    MsgSwapExactAmountIn, MsgSwapExactAmountInResponse, SwapAmountInRoute,
+};
+use std::str::FromStr;
+use vaultenator::config::Configure;
+use vaultenator::errors::ContractError as VaultenatorError;
+use vaultenator::state::ManageState;
+
+pub const HARVEST_CLAIM_REPLY_ID: u64 = 12;
+pub const HARVEST_SWAP_REPLY_ID: u64 = 13;
+
+/// One pool in a swap route and the denom it swaps to.
+#[cw_serde]
+pub struct SwapHop {
+    pub pool_id: u64,
+    pub token_out_denom: String,
+}
+
+/// Swap route used to sell reward tokens of `denom` for the base denom.
+#[cw_serde]
+pub struct RewardRoute {
+    pub denom: String,
+    pub hops: Vec<SwapHop>,
+}
+
+impl RewardRoute {
+    /// Checks the route ends in the base denom and starts from a reward
+    /// denom. Harvests sell the whole balance of a reward denom, so it cannot
+    /// be a denom the vault's assets are held in or its own strategy token.
+    pub fn validate(&self, config: &MyConfig) -> Result<(), ContractError> {
+        let ends_in_base = self
+            .hops
+            .last()
+            .is_some_and(|hop| hop.token_out_denom == config.base_denom);
+
+        ensure!(
+            !vault_denoms(config).contains(&self.denom) && ends_in_base,
+            ContractError::InvalidRewardRoute {
+                denom: self.denom.clone()
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Least the route may return for `amount`, each hop may return
+    /// `max_slippage` less than its pool's TWAP implies.
+    fn min_amount_out(
+        &self,
+        deps: Deps,
+        env: &Env,
+        amount: Uint128,
+        twap_window: u64,
+        max_slippage: Decimal,
+    ) -> StdResult<Uint128> {
+        let mut denom_in = &self.denom;
+        let mut amount_out = amount;
+        for hop in &self.hops {
+            let price = twap_price(
+                deps,
+                env,
+                hop.pool_id,
+                denom_in,
+                &hop.token_out_denom,
+                twap_window,
+            )?;
+            amount_out = min_amount_out(amount_out, price, max_slippage);
+            denom_in = &hop.token_out_denom;
+        }
+
+        Ok(amount_out)
+    }
+}
+
+impl std::fmt::Display for RewardRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.denom)?;
+        for hop in &self.hops {
+            write!(f, ">{}:{}", hop.pool_id, hop.token_out_denom)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks every route is valid and sells a different denom.
+pub fn validate_reward_routes(
+    routes: &[RewardRoute],
+    config: &MyConfig,
+) -> Result<(), ContractError> {
+    for (i, route) in routes.iter().enumerate() {
+        route.validate(config)?;
+        ensure!(
+            !routes[..i].iter().any(|other| other.denom == route.denom),
+            ContractError::InvalidRewardRoute {
+                denom: route.denom.clone()
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Denoms the vault holds its assets in and its strategy token.
+fn vault_denoms(config: &MyConfig) -> Vec<String> {
+    let mut denoms = vec![config.base_denom.clone()];
+    if let Some(strategy) = &config.strategy {
+        denoms.extend(strategy.asset_denoms(&config.base_denom));
+    }
+    denoms.extend(config.strategy_denom.clone());
+    denoms
+}
+
+/// Harvest in progress while rewards are claimed and swapped through
+/// submessage replies.
+#[cw_serde]
+pub struct PendingHarvest {
+    /// Balances of the base denom and reward denoms before claiming
+    pub balances: Vec<OsmosisCoin>,
+    /// Tokens received from claiming rewards
+    pub claimed: Vec<OsmosisCoin>,
+    /// Base tokens collected so far to reinvest
+    pub reinvest_amount: Uint128,
+    /// Reward swaps that have not replied yet
+    pub pending_swaps: u64,
+}
+
+pub const PENDING_HARVEST: Item<PendingHarvest> = Item::new("pending_harvest");
+
+impl MyVault {
+    /// Claims the strategy's rewards, swaps them to the base denom along the
+    /// configured reward routes and reinvests the proceeds.
+    pub(crate) fn harvest(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        assert_any_role(
+            deps.as_ref(),
+            &info.sender,
+            &[Role::Strategist, Role::Keeper],
+        )?;
+        // Harvests reinvest, which stays blocked while the vault is paused
+        MyState::is_open_and_unpaused(deps.as_ref())?;
+
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+
+        let harvest = PendingHarvest {
+            balances: reward_balances(deps.as_ref(), &env, &config)?,
+            claimed: vec![],
+            reinvest_amount: Uint128::zero(),
+            pending_swaps: 0,
+        };
+
+        let mut claim_msgs = match &config.strategy {
+            Some(strategy) => strategy.claim_msgs(deps.as_ref(), &env)?,
+            None => vec![],
+        };
+
+        // Swap once the last claim has executed
+        let Some(last_claim_msg) = claim_msgs.pop() else {
+            return swap_rewards(deps, &env, &config, harvest);
+        };
+
+        PENDING_HARVEST.save(deps.storage, &harvest)?;
+
+        Ok(Response::new()
+            .add_messages(claim_msgs)
+            .add_submessage(SubMsg::reply_on_success(
+                last_claim_msg,
+                HARVEST_CLAIM_REPLY_ID,
+            )))
+    }
+}
+
+pub fn handle_reply(
+    deps: DepsMut,
+    env: &Env,
+    config: &MyConfig,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let mut harvest = PENDING_HARVEST.load(deps.storage)?;
+
+    match msg.id {
+        HARVEST_CLAIM_REPLY_ID => swap_rewards(deps, env, config, harvest),
+        HARVEST_SWAP_REPLY_ID => {
+            let response: MsgSwapExactAmountInResponse = msg.result.try_into()?;
+
+            harvest.reinvest_amount += Uint128::from_str(&response.token_out_amount)?;
+            harvest.pending_swaps -= 1;
+
+            if harvest.pending_swaps > 0 {
+                PENDING_HARVEST.save(deps.storage, &harvest)?;
+                return Ok(Response::new());
+            }
+
+            reinvest(deps, env, config, harvest)
+        }
+        _ => Err(VaultenatorError::InvalidReplyId.into()),
+    }
+}
+
+/// Balances of the base denom and every reward denom held by the contract.
+fn reward_balances(
+    deps: Deps,
+    env: &Env,
+    config: &MyConfig,
+) -> Result<Vec<OsmosisCoin>, ContractError> {
+    std::iter::once(&config.base_denom)
+        .chain(config.reward_routes.iter().map(|route| &route.denom))
+        .map(|denom| {
+            Ok(OsmosisCoin {
+                denom: denom.to_string(),
+                amount: query_balance(deps, env, denom)?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Records what the claim paid out and swaps the reward tokens held to the
+/// base denom, reinvesting straight away if there is nothing to swap.
+fn swap_rewards(
+    deps: DepsMut,
+    env: &Env,
+    config: &MyConfig,
+    mut harvest: PendingHarvest,
+) -> Result<Response, ContractError> {
+    for balance in &harvest.balances {
+        let claimed = query_balance(deps.as_ref(), env, &balance.denom)?
+            .saturating_sub(Uint128::from_str(&balance.amount)?);
+        if !claimed.is_zero() {
+            harvest.claimed.push(OsmosisCoin {
+                denom: balance.denom.clone(),
+                amount: claimed.to_string(),
+            });
+        }
+    }
+
+    if let Some(base_claimed) = harvest
+        .claimed
+        .iter()
+        .find(|coin| coin.denom == config.base_denom)
+    {
+        harvest.reinvest_amount += Uint128::from_str(&base_claimed.amount)?;
+    }
+
+    // Reward swaps are bounded like the strategy's own swaps
+    let (twap_window, max_slippage) = config.strategy.as_ref().map_or(
+        (DEFAULT_TWAP_WINDOW, DEFAULT_MAX_SLIPPAGE),
+        Strategy::twap_params,
+    );
+
+    // Sell the whole balance so rewards sent to the vault directly are
+    // compounded too
+    let mut swap_msgs = vec![];
+    for route in &config.reward_routes {
+        let amount = query_balance(deps.as_ref(), env, &route.denom)?;
+        if amount.is_zero() {
+            continue;
+        }
+
+        let msg = MsgSwapExactAmountIn {
+            sender: env.contract.address.to_string(),
+            routes: route
+                .hops
+                .iter()
+                .map(|hop| SwapAmountInRoute {
+                    pool_id: hop.pool_id,
+                    token_out_denom: hop.token_out_denom.clone(),
+                })
+                .collect(),
+            token_in: Some(OsmosisCoin {
+                denom: route.denom.clone(),
+                amount: amount.to_string(),
+            }),
+            token_out_min_amount: route
+                .min_amount_out(deps.as_ref(), env, amount, twap_window, max_slippage)?
+                .to_string(),
+        };
+        swap_msgs.push(SubMsg::reply_on_success(msg, HARVEST_SWAP_REPLY_ID));
+    }
+
+    if swap_msgs.is_empty() {
+        return reinvest(deps, env, config, harvest);
+    }
+
+    harvest.pending_swaps = swap_msgs.len() as u64;
+    PENDING_HARVEST.save(deps.storage, &harvest)?;
+
+    Ok(Response::new().add_submessages(swap_msgs))
+}
+
+/// Deploys the harvested base tokens to the strategy, they stay on the
+/// contract if there is none.
+fn reinvest(
+    mut deps: DepsMut,
+    env: &Env,
+    config: &MyConfig,
+    harvest: PendingHarvest,
+) -> Result<Response, ContractError> {
+    PENDING_HARVEST.remove(deps.storage);
+
+    // The harvested base tokens are held idle here, so the snapshot prices
+    // them in
+    record_current_share_price(deps.branch(), env, config)?;
+
+    let invest_msgs = match &config.strategy {
+        Some(strategy) if !harvest.reinvest_amount.is_zero() => {
+            strategy.invest(deps, env, &config.base_denom, harvest.reinvest_amount)?
+        }
+        _ => vec![],
+    };
+
+    // Attribute values must not be empty
+    let claimed = match harvest.claimed.is_empty() {
+        true => "none".to_string(),
+        false => display_coins(&harvest.claimed),
+    };
+
+    let event = Event::new("harvest").add_attributes(vec![
+        ("claimed", claimed),
+        ("reinvested", harvest.reinvest_amount.to_string()),
+        ("denom", config.base_denom.clone()),
+    ]);
+
+    Ok(Response::new()
+        .add_submessages(invest_msgs)
+        .add_event(event))
+}
@@ -1,6 +1,23 @@
+pub mod admin;
+pub mod concentrated_liquidity;
 pub mod config;
 pub mod contract;
 pub mod describe;
+pub mod errors;
+pub mod fees;
+pub mod force_unlock;
+pub mod gamm;
 pub mod handle;
+pub mod harvest;
+pub mod lockup;
 pub mod msg;
+pub mod pause;
+pub mod positions;
+pub mod pricing;
+pub mod query;
+pub mod reply;
+pub mod roles;
+pub mod share_price;
 pub mod state;
+pub mod strategy;
+pub mod timelock;
@@ -0,0 +1,332 @@
+use crate::config::MyConfig;
+use crate::contract::MyVault;
+use crate::errors::ContractError;
+use crate::fees::{accrue_fees, mint_accrued_fees, pending_fees};
+use crate::handle::must_pay_amount;
+use crate::pricing::{total_assets, total_supply};
+use crate::share_price::record_share_price;
+use crate::state::MyState;
+use crate::strategy::{withdraw, WithdrawalKind};
+use cosmwasm_std::{
+    coin, ensure, ensure_eq, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, Event,
+    MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, MultiIndex};
+use cw_vault_standard::extensions::lockup::{
+    LockupExecuteMsg, LockupQueryMsg, UnlockingPosition, UNLOCKING_POSITION_ATTR_KEY,
+    UNLOCKING_POSITION_CREATED_EVENT_TYPE,
+};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgBurn;
+use vaultenator::config::Configure;
+use vaultenator::errors::ContractError as VaultenatorError;
+use vaultenator::state::ManageState;
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+pub const NEXT_LOCKUP_ID: Item<u64> = Item::new("next_lockup_id");
+/// Base tokens held for unlocking positions, excluded from total assets
+pub const TOTAL_UNLOCKING: Item<Uint128> = Item::new("total_unlocking");
+
+pub struct UnlockingPositionIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, UnlockingPosition, u64>,
+}
+
+impl<'a> IndexList<UnlockingPosition> for UnlockingPositionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<UnlockingPosition>> + '_> {
+        let v: Vec<&dyn Index<UnlockingPosition>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn unlocking_positions<'a>(
+) -> IndexedMap<'a, u64, UnlockingPosition, UnlockingPositionIndexes<'a>> {
+    let indexes = UnlockingPositionIndexes {
+        owner: MultiIndex::new(
+            |_pk, position| position.owner.clone(),
+            "unlocking_positions",
+            "unlocking_positions__owner",
+        ),
+    };
+    IndexedMap::new("unlocking_positions", indexes)
+}
+
+pub fn total_unlocking(deps: Deps) -> StdResult<Uint128> {
+    Ok(TOTAL_UNLOCKING.may_load(deps.storage)?.unwrap_or_default())
+}
+
+/// Creates an unlocking position for `base_token_amount` base tokens held on
+/// the contract, releasing them after the lockup duration.
+pub fn create_unlocking_position(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &MyConfig,
+    owner: Addr,
+    base_token_amount: Uint128,
+) -> StdResult<Event> {
+    let lockup_id = NEXT_LOCKUP_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_LOCKUP_ID.save(storage, &(lockup_id + 1))?;
+
+    let position = UnlockingPosition {
+        id: lockup_id,
+        owner,
+        release_at: config.lockup_duration.after(&env.block),
+        base_token_amount,
+    };
+    unlocking_positions().save(storage, lockup_id, &position)?;
+
+    let total_unlocking =
+        TOTAL_UNLOCKING.may_load(storage)?.unwrap_or_default() + base_token_amount;
+    TOTAL_UNLOCKING.save(storage, &total_unlocking)?;
+
+    Ok(
+        Event::new(UNLOCKING_POSITION_CREATED_EVENT_TYPE).add_attributes(vec![
+            (UNLOCKING_POSITION_ATTR_KEY, lockup_id.to_string()),
+            ("owner", position.owner.to_string()),
+            ("release_at", position.release_at.to_string()),
+            ("base_token_amount", base_token_amount.to_string()),
+        ]),
+    )
+}
+
+/// Releases `amount` of base tokens from an unlocking position, removing the
+/// position once it is empty.
+pub fn release_unlocking(
+    storage: &mut dyn Storage,
+    mut position: UnlockingPosition,
+    amount: Uint128,
+) -> StdResult<()> {
+    position.base_token_amount = position.base_token_amount.checked_sub(amount)?;
+
+    if position.base_token_amount.is_zero() {
+        unlocking_positions().remove(storage, position.id)?;
+    } else {
+        unlocking_positions().save(storage, position.id, &position)?;
+    }
+
+    let total_unlocking = TOTAL_UNLOCKING
+        .may_load(storage)?
+        .unwrap_or_default()
+        .checked_sub(amount)?;
+    TOTAL_UNLOCKING.save(storage, &total_unlocking)
+}
+
+impl MyVault {
+    pub fn execute_lockup(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: LockupExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        match msg {
+            LockupExecuteMsg::Unlock { amount } => self.unlock(deps, env, info, amount),
+            LockupExecuteMsg::EmergencyUnlock { amount } => {
+                self.emergency_unlock(deps, env, info, amount)
+            }
+            LockupExecuteMsg::WithdrawUnlocked {
+                recipient,
+                lockup_id,
+            } => self.withdraw_unlocked(deps, env, info, recipient, lockup_id),
+        }
+    }
+
+    pub fn query_lockup(&self, deps: Deps, msg: LockupQueryMsg) -> StdResult<Binary> {
+        match msg {
+            LockupQueryMsg::UnlockingPositions {
+                owner,
+                start_after,
+                limit,
+            } => to_json_binary(&Self::query_unlocking_positions(
+                deps,
+                owner,
+                start_after,
+                limit,
+            )?),
+            LockupQueryMsg::UnlockingPosition { lockup_id } => {
+                to_json_binary(&unlocking_positions().load(deps.storage, lockup_id)?)
+            }
+            LockupQueryMsg::LockupDuration {} => {
+                let config = MyConfig::get_from_storage(deps)
+                    .map_err(|e| StdError::generic_err(e.to_string()))?;
+                to_json_binary(&config.lockup_duration)
+            }
+        }
+    }
+
+    /// Burns the vault tokens sent and creates an unlocking position for the
+    /// base tokens they are worth at the current share price.
+    fn unlock(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        MyState::assert_can_withdraw(deps.as_ref(), &env)?;
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+
+        let strategy_denom = config
+            .strategy_denom
+            .as_ref()
+            .ok_or(VaultenatorError::DenomNotInitialized {})?;
+
+        let strategy_denom_amount = must_pay_amount(&info, strategy_denom, amount)?;
+
+        let total_assets = total_assets(deps.as_ref(), &env, &config)?;
+        let total_supply = total_supply(deps.as_ref(), strategy_denom)?;
+        let fees = accrue_fees(&mut deps, &env, &config, total_assets, total_supply)?;
+
+        let burn_strategy_token_msg = MsgBurn {
+            sender: env.contract.address.to_string(),
+            amount: Some(OsmosisCoin {
+                denom: strategy_denom.to_string(),
+                amount: strategy_denom_amount.to_string(),
+            }),
+            burn_from_address: env.contract.address.to_string(),
+        };
+
+        let response = Response::new().add_message(burn_strategy_token_msg);
+        let response = mint_accrued_fees(response, &env, &config, &fees)?;
+
+        // The withdrawal fee is taken when unlocking so the position holds
+        // exactly what the owner can withdraw
+        withdraw(
+            response,
+            deps,
+            &env,
+            &config,
+            WithdrawalKind::Unlock { owner: info.sender },
+            strategy_denom_amount,
+            total_assets,
+            total_supply + fees.total(),
+        )
+    }
+
+    /// Unlocks like `unlock` without minting accrued fees first, so fee
+    /// minting cannot block the unlock. The vault tokens are still priced
+    /// against the supply including the pending fee shares, so the unlock
+    /// pays the same fees as `unlock` and leaves them in the vault. Unlocking
+    /// positions hold base tokens, so a strategy is still exited and its pair
+    /// tokens swapped to base tokens.
+    fn emergency_unlock(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        MyState::assert_can_withdraw(deps.as_ref(), &env)?;
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+
+        let strategy_denom = config
+            .strategy_denom
+            .as_ref()
+            .ok_or(VaultenatorError::DenomNotInitialized {})?;
+
+        let strategy_denom_amount = must_pay_amount(&info, strategy_denom, amount)?;
+
+        let state = MyState::get_from_storage(deps.as_ref())?;
+        let total_assets = total_assets(deps.as_ref(), &env, &config)?;
+        let total_supply = total_supply(deps.as_ref(), strategy_denom)?;
+        let (fees, _) = pending_fees(&config, &state, total_assets, total_supply, env.block.time);
+        record_share_price(
+            deps.storage,
+            &env,
+            &config,
+            total_assets,
+            total_supply + fees.total(),
+        )?;
+
+        let burn_strategy_token_msg = MsgBurn {
+            sender: env.contract.address.to_string(),
+            amount: Some(OsmosisCoin {
+                denom: strategy_denom.to_string(),
+                amount: strategy_denom_amount.to_string(),
+            }),
+            burn_from_address: env.contract.address.to_string(),
+        };
+
+        withdraw(
+            Response::new().add_message(burn_strategy_token_msg),
+            deps,
+            &env,
+            &config,
+            WithdrawalKind::Unlock { owner: info.sender },
+            strategy_denom_amount,
+            total_assets,
+            total_supply + fees.total(),
+        )
+    }
+
+    fn withdraw_unlocked(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: Option<String>,
+        lockup_id: u64,
+    ) -> Result<Response, ContractError> {
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+
+        let position = unlocking_positions()
+            .may_load(deps.storage, lockup_id)?
+            .ok_or(ContractError::UnlockingPositionNotFound { lockup_id })?;
+
+        ensure_eq!(
+            position.owner,
+            info.sender,
+            VaultenatorError::Unauthorized {}
+        );
+        ensure!(
+            position.release_at.is_expired(&env.block),
+            ContractError::UnlockingPositionLocked { lockup_id }
+        );
+
+        let recipient = match recipient {
+            Some(recipient) => deps.api.addr_validate(&recipient)?,
+            None => info.sender,
+        };
+
+        let base_token_amount = position.base_token_amount;
+        release_unlocking(deps.storage, position, base_token_amount)?;
+
+        let msg_transfer = BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(base_token_amount.into(), config.base_denom)],
+        };
+
+        let event = Event::new("withdraw_unlocked").add_attributes(vec![
+            ("lockup_id", lockup_id.to_string()),
+            ("recipient", recipient.to_string()),
+            ("base_token_amount", base_token_amount.to_string()),
+        ]);
+
+        Ok(Response::new().add_message(msg_transfer).add_event(event))
+    }
+
+    fn query_unlocking_positions(
+        deps: Deps,
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<UnlockingPosition>> {
+        let owner = deps.api.addr_validate(&owner)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        unlocking_positions()
+            .idx
+            .owner
+            .prefix(owner)
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| item.map(|(_, position)| position))
+            .collect()
+    }
+}
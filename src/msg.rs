@@ -1,5 +1,309 @@
+use crate::harvest::RewardRoute;
+use crate::roles::Role;
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Int128, SignedDecimal, StdError, StdResult, Timestamp, Uint128};
+use cw_utils::Duration;
+use cw_vault_standard::extensions::force_unlock::ForceUnlockExecuteMsg;
+use cw_vault_standard::extensions::lockup::{LockupExecuteMsg, LockupQueryMsg};
+use cw_vault_standard::{VaultStandardExecuteMsg, VaultStandardQueryMsg};
+use serde::{Deserialize, Deserializer};
+use vaultenator::msg::{
+    ExecuteMsg as VaultenatorExecuteMsg, ExtensionExecuteMsg as VaultenatorExtensionExecute,
+    ExtensionQueryMsg as VaultenatorExtensionQuery, QueryMsg as VaultenatorQueryMsg,
+    VaultenatorExtensionExecuteMsg, VaultenatorExtensionQueryMsg,
+};
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub base_denom: String,
+    pub strategy_cap: Option<Uint128>,
+    /// Time vault tokens are locked for after `Unlock`, no lockup if unset
+    pub lockup_duration: Option<Duration>,
+    /// Fee rates default to zero, a fee recipient is required if any is set
+    pub deposit_fee: Option<Decimal>,
+    pub withdrawal_fee: Option<Decimal>,
+    pub performance_fee: Option<Decimal>,
+    /// Annual management fee rate
+    pub management_fee: Option<Decimal>,
+    pub fee_recipient: Option<String>,
+    /// Where deposits are deployed, held on the contract if unset
+    pub strategy: Option<StrategyMsg>,
+    /// Swap routes `Harvest` sells reward tokens along
+    pub reward_routes: Option<Vec<RewardRoute>>,
+    /// Seconds config changes are queued for before they can be executed,
+    /// applied immediately if unset
+    pub config_timelock: Option<u64>,
+    /// Seconds after which a pause lapses, pauses last until unpaused if
+    /// unset
+    pub max_pause_duration: Option<u64>,
+    /// Minimum seconds between share price snapshots, a snapshot is taken on
+    /// every deposit, redeem, unlock, harvest, rebalance and fee accrual if
+    /// unset
+    pub share_price_snapshot_interval: Option<u64>,
+}
+
+#[cw_serde]
+pub enum StrategyMsg {
+    /// Provide liquidity to a two asset GAMM pool weighting the base denom
+    /// and one other asset equally. Pair tokens are priced at their TWAP over
+    /// `twap_window` seconds and swaps may return `max_slippage` less than
+    /// the TWAP implies, defaulting to `DEFAULT_TWAP_WINDOW` and
+    /// `DEFAULT_MAX_SLIPPAGE`.
+    Gamm {
+        pool_id: u64,
+        twap_window: Option<u64>,
+        max_slippage: Option<Decimal>,
+    },
+    /// Provide liquidity between `lower_tick` and `upper_tick` in a
+    /// concentrated liquidity pool containing the base denom. The position is
+    /// priced at the pool's TWAP over `twap_window` seconds and swaps and
+    /// positions may return `max_slippage` less than the TWAP implies, with
+    /// the same defaults as `Gamm`.
+    ConcentratedLiquidity {
+        pool_id: u64,
+        lower_tick: i64,
+        upper_tick: i64,
+        twap_window: Option<u64>,
+        max_slippage: Option<Decimal>,
+    },
+}
+
+#[cw_serde]
+pub enum ExtensionExecuteMsg {
+    Vaultenator(VaultenatorExtensionExecuteMsg),
+    Lockup(LockupExecuteMsg),
+    ForceUnlock(ForceUnlockExecuteMsg),
+    MyVault(MyVaultExtensionExecuteMsg),
+}
+
+#[cw_serde]
+pub enum MyVaultExtensionExecuteMsg {
+    /// Update the config, fields that are not set are left unchanged. Only
+    /// allowed while the config timelock is zero or for changes that tighten
+    /// the config, otherwise changes must be proposed.
+    UpdateConfig(UpdateConfigMsg),
+    /// Queue a config change to be executed once the config timelock has
+    /// passed.
+    ProposeConfigChange(UpdateConfigMsg),
+    /// Apply a queued config change whose timelock has passed.
+    ExecuteConfigChange { id: u64 },
+    /// Drop a queued config change.
+    CancelConfigChange { id: u64 },
+    /// Accrue the management fee up to the current block and crystallise the
+    /// performance fee, callable by anyone.
+    AccrueFees {},
+    /// Move the concentrated liquidity position to a new tick range, owner,
+    /// strategist or keeper only.
+    Rebalance { lower_tick: i64, upper_tick: i64 },
+    /// Claim strategy rewards, swap them to the base denom along the reward
+    /// routes and reinvest them, owner, strategist or keeper only.
+    Harvest {},
+    /// Pause the vault recording why, pauser, guardian or keeper only.
+    Pause { reason: Option<String> },
+    /// Unpause the vault recording why, pauser only.
+    Unpause { reason: Option<String> },
+    /// Grant `role` to `address`, owner only.
+    GrantRole { role: Role, address: String },
+    /// Revoke `role` from `address`, owner only.
+    RevokeRole { role: Role, address: String },
+}
+
+/// Partial update of `MyConfig`.
+///
+/// Fields that can be unset take a nested option, omit the field to leave it
+/// unchanged or pass `null` to unset it.
+#[cw_serde]
+#[derive(Default)]
+pub struct UpdateConfigMsg {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "double_option"
+    )]
+    pub strategy_cap: Option<Option<Uint128>>,
+    pub lockup_duration: Option<Duration>,
+    pub deposit_fee: Option<Decimal>,
+    pub withdrawal_fee: Option<Decimal>,
+    pub performance_fee: Option<Decimal>,
+    pub management_fee: Option<Decimal>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "double_option"
+    )]
+    pub fee_recipient: Option<Option<String>>,
+    /// Replaces all reward routes
+    pub reward_routes: Option<Vec<RewardRoute>>,
+    pub config_timelock: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "double_option"
+    )]
+    pub max_pause_duration: Option<Option<u64>>,
+    pub share_price_snapshot_interval: Option<u64>,
+}
+
+impl UpdateConfigMsg {
+    /// Whether the update changes fee rates or the fee recipient, which
+    /// requires the fee manager role.
+    pub fn changes_fees(&self) -> bool {
+        self.deposit_fee.is_some()
+            || self.withdrawal_fee.is_some()
+            || self.performance_fee.is_some()
+            || self.management_fee.is_some()
+            || self.fee_recipient.is_some()
+    }
+
+    /// Whether the update changes anything other than fees, which requires
+    /// the config admin role.
+    pub fn changes_settings(&self) -> bool {
+        self.strategy_cap.is_some()
+            || self.lockup_duration.is_some()
+            || self.reward_routes.is_some()
+            || self.config_timelock.is_some()
+            || self.max_pause_duration.is_some()
+            || self.share_price_snapshot_interval.is_some()
+    }
+}
+
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Deserialize::deserialize(deserializer).map(Some)
+}
+
+#[cw_serde]
+pub enum ExtensionQueryMsg {
+    Vaultenator(VaultenatorExtensionQueryMsg),
+    Lockup(LockupQueryMsg),
+    MyVault(MyVaultExtensionQueryMsg),
+}
+
+#[cw_serde]
+pub enum MyVaultExtensionQueryMsg {
+    /// Returns `Option<Uint128>` amount of base tokens that can still be
+    /// deposited before the strategy cap is reached, `None` if uncapped.
+    RemainingCapacity {},
+    /// Returns `PendingFeesResponse` with fees accrued since the last
+    /// accrual that have not been minted yet.
+    PendingFees {},
+    /// Returns `bool` whether `address` has been granted `role`, the owner
+    /// holds every role without being granted it.
+    HasRole { role: Role, address: String },
+    /// Returns `Vec<Addr>` of the addresses granted `role`.
+    RoleMembers {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns `Vec<PauseRecord>` of the most recent pauses, unpauses and
+    /// opens by id.
+    PauseHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns `Vec<PendingConfigChange>` of queued config changes by id.
+    PendingConfigChanges {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns `UserPositionResponse` with the shares `address` holds and its
+    /// cost basis.
+    UserPosition { address: String },
+    /// Returns `Vec<SharePriceSnapshot>` of the most recent share price
+    /// snapshots by id.
+    SharePriceHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns `Option<AprResponse>` annualising the share price change over
+    /// the snapshots taken in the last `window_seconds`, `None` until two
+    /// snapshots at different times fall within the window.
+    Apr { window_seconds: u64 },
+    /// Returns `Vec<Depositor>` of the addresses holding shares they deposited
+    /// for, ordered by address, with their current share balances.
+    Depositors {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct PendingFeesResponse {
+    /// Strategy tokens owed to the fee recipient for the management fee
+    pub management_fee: Uint128,
+    /// Strategy tokens the next crystallisation mints for the performance fee
+    pub performance_fee: Uint128,
+    /// Share price the performance fee has been charged up to
+    pub high_water_mark: Decimal,
+}
+
+#[cw_serde]
+pub struct AprResponse {
+    /// Simple annualised return, negative if the share price fell
+    pub apr: SignedDecimal,
+    /// Time of the snapshot the return is measured from
+    pub start: Timestamp,
+    /// Time of the latest snapshot
+    pub end: Timestamp,
+}
+
+#[cw_serde]
+pub struct UserPositionResponse {
+    /// Strategy tokens held by the address
+    pub shares: Uint128,
+    /// Base tokens the shares would redeem for after the withdrawal fee
+    pub value: Uint128,
+    /// Base tokens deposited for the address, including the deposit fee
+    pub deposited: Uint128,
+    /// Base tokens the address has redeemed or unlocked
+    pub withdrawn: Uint128,
+    /// Unrealised profit or loss, `value + withdrawn - deposited`
+    pub pnl: Int128,
+}
+
+pub type ExecuteMsg = VaultStandardExecuteMsg<ExtensionExecuteMsg>;
+pub type QueryMsg = VaultStandardQueryMsg<ExtensionQueryMsg>;
+
+/// Converts messages that are not specific to this vault into the Vaultenator
+/// execute message so they can be handled by the default implementation.
+pub fn to_vaultenator_execute_msg(msg: ExecuteMsg) -> StdResult<VaultenatorExecuteMsg> {
+    match msg {
+        ExecuteMsg::Deposit { amount, recipient } => {
+            Ok(VaultenatorExecuteMsg::Deposit { amount, recipient })
+        }
+        ExecuteMsg::Redeem { recipient, amount } => {
+            Ok(VaultenatorExecuteMsg::Redeem { recipient, amount })
+        }
+        ExecuteMsg::VaultExtension(ExtensionExecuteMsg::Vaultenator(msg)) => Ok(
+            VaultenatorExecuteMsg::VaultExtension(VaultenatorExtensionExecute::Vaultenator(msg)),
+        ),
+        ExecuteMsg::VaultExtension(_) => Err(StdError::generic_err(
+            "Message is not handled by Vaultenator",
+        )),
+    }
+}
+
+/// Converts queries that are not specific to this vault into the Vaultenator
+/// query message so they can be answered by the default implementation.
+pub fn to_vaultenator_query_msg(msg: QueryMsg) -> StdResult<VaultenatorQueryMsg> {
+    match msg {
+        QueryMsg::VaultStandardInfo {} => Ok(VaultenatorQueryMsg::VaultStandardInfo {}),
+        QueryMsg::Info {} => Ok(VaultenatorQueryMsg::Info {}),
+        QueryMsg::PreviewDeposit { amount } => Ok(VaultenatorQueryMsg::PreviewDeposit { amount }),
+        QueryMsg::PreviewRedeem { amount } => Ok(VaultenatorQueryMsg::PreviewRedeem { amount }),
+        QueryMsg::TotalAssets {} => Ok(VaultenatorQueryMsg::TotalAssets {}),
+        QueryMsg::TotalVaultTokenSupply {} => Ok(VaultenatorQueryMsg::TotalVaultTokenSupply {}),
+        QueryMsg::ConvertToShares { amount } => Ok(VaultenatorQueryMsg::ConvertToShares { amount }),
+        QueryMsg::ConvertToAssets { amount } => Ok(VaultenatorQueryMsg::ConvertToAssets { amount }),
+        QueryMsg::VaultExtension(ExtensionQueryMsg::Vaultenator(msg)) => Ok(
+            VaultenatorQueryMsg::VaultExtension(VaultenatorExtensionQuery::Vaultenator(msg)),
+        ),
+        QueryMsg::VaultExtension(_) => {
+            Err(StdError::generic_err("Query is not handled by Vaultenator"))
+        }
+    }
 }
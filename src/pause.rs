@@ -0,0 +1,150 @@
+use crate::admin::{open_contract, pause_contract, unpause_contract};
+use crate::contract::MyVault;
+use crate::errors::ContractError;
+use crate::lockup::{DEFAULT_LIMIT, MAX_LIMIT};
+use crate::state::MyState;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    ensure, Addr, Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult, Storage,
+    Timestamp,
+};
+use cw_storage_plus::{Bound, Item, Map};
+use vaultenator::state::ManageState;
+
+/// Number of pause records kept, older records are pruned
+pub const MAX_PAUSE_HISTORY: u64 = 100;
+pub const MAX_PAUSE_REASON_LENGTH: usize = 256;
+
+/// Pause, unpause or open of the vault.
+#[cw_serde]
+pub struct PauseRecord {
+    pub id: u64,
+    /// Whether the vault was paused, `false` for unpausing or opening
+    pub paused: bool,
+    pub actor: Addr,
+    pub reason: Option<String>,
+    pub timestamp: Timestamp,
+}
+
+pub const NEXT_PAUSE_RECORD_ID: Item<u64> = Item::new("next_pause_record_id");
+pub const PAUSE_HISTORY: Map<u64, PauseRecord> = Map::new("pause_history");
+
+pub fn pause_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PauseRecord>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    PAUSE_HISTORY
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect()
+}
+
+/// Appends a record to the pause history, dropping the oldest once it holds
+/// more than `MAX_PAUSE_HISTORY` records.
+fn record_pause(
+    storage: &mut dyn Storage,
+    env: &Env,
+    paused: bool,
+    actor: Addr,
+    reason: Option<String>,
+) -> Result<Event, ContractError> {
+    if let Some(reason) = &reason {
+        ensure!(
+            reason.len() <= MAX_PAUSE_REASON_LENGTH,
+            ContractError::PauseReasonTooLong {
+                max: MAX_PAUSE_REASON_LENGTH
+            }
+        );
+    }
+
+    let id = NEXT_PAUSE_RECORD_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_PAUSE_RECORD_ID.save(storage, &(id + 1))?;
+    if id > MAX_PAUSE_HISTORY {
+        PAUSE_HISTORY.remove(storage, id - MAX_PAUSE_HISTORY);
+    }
+
+    let record = PauseRecord {
+        id,
+        paused,
+        actor,
+        reason,
+        timestamp: env.block.time,
+    };
+    PAUSE_HISTORY.save(storage, id, &record)?;
+
+    let event_type = match paused {
+        true => "paused",
+        false => "unpaused",
+    };
+
+    Ok(Event::new(event_type).add_attributes(vec![
+        ("pause_record_id", id.to_string()),
+        ("actor", record.actor.to_string()),
+        // Attribute values must not be empty
+        ("reason", record.reason.unwrap_or("none".to_string())),
+    ]))
+}
+
+impl MyVault {
+    pub(crate) fn execute_open(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let was_paused = MyState::is_contract_paused(deps.as_ref())?;
+        let mut state = open_contract(&mut deps, &info.sender)?;
+
+        let mut response = Response::new().add_event(Event::new("open_contract"));
+        if was_paused {
+            state.last_unpause = Some(env.block.time);
+            state.save_to_storage(&mut deps)?;
+
+            let event = record_pause(deps.storage, &env, false, info.sender, None)?;
+            response = response.add_event(event);
+        }
+
+        Ok(response)
+    }
+
+    pub(crate) fn execute_pause(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        reason: Option<String>,
+    ) -> Result<Response, ContractError> {
+        let mut state = pause_contract(&mut deps, &info.sender)?;
+        state.last_pause = env.block.time;
+        state.save_to_storage(&mut deps)?;
+
+        let event = record_pause(deps.storage, &env, true, info.sender, reason)?;
+
+        Ok(Response::new().add_event(event))
+    }
+
+    pub(crate) fn execute_unpause(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        reason: Option<String>,
+    ) -> Result<Response, ContractError> {
+        let mut state = unpause_contract(&mut deps, &info.sender)?;
+        state.last_unpause = Some(env.block.time);
+        state.save_to_storage(&mut deps)?;
+
+        let event = record_pause(deps.storage, &env, false, info.sender, reason)?;
+
+        Ok(Response::new().add_event(event))
+    }
+}
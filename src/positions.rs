@@ -0,0 +1,90 @@
+use crate::lockup::{DEFAULT_LIMIT, MAX_LIMIT};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Deps, DepsMut, Empty, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Map};
+
+/// Base tokens an address has put into and taken out of the vault.
+///
+/// Shares transferred between addresses move value without updating either
+/// side's totals.
+#[cw_serde]
+#[derive(Default)]
+pub struct UserTotals {
+    /// Base tokens deposited for the address, including the deposit fee
+    pub deposited: Uint128,
+    /// Base tokens redeemed or unlocked by the address, after the withdrawal
+    /// fee
+    pub withdrawn: Uint128,
+}
+
+/// Address that has deposited into the vault and still holds shares.
+#[cw_serde]
+pub struct Depositor {
+    pub address: Addr,
+    /// Strategy tokens held by the address when queried
+    pub shares: Uint128,
+}
+
+pub const USER_TOTALS: Map<&Addr, UserTotals> = Map::new("user_totals");
+
+/// Addresses deposited for, removed once a redeem or unlock leaves them
+/// without shares. Totals are kept in `USER_TOTALS` after an address exits.
+pub const DEPOSITORS: Map<&Addr, Empty> = Map::new("depositors");
+
+pub fn user_totals(storage: &dyn Storage, address: &Addr) -> StdResult<UserTotals> {
+    Ok(USER_TOTALS.may_load(storage, address)?.unwrap_or_default())
+}
+
+pub fn record_deposit(storage: &mut dyn Storage, address: &Addr, amount: Uint128) -> StdResult<()> {
+    let mut totals = user_totals(storage, address)?;
+    totals.deposited += amount;
+    USER_TOTALS.save(storage, address, &totals)?;
+    DEPOSITORS.save(storage, address, &Empty {})
+}
+
+/// Records base tokens withdrawn by `address`, dropping it from the
+/// depositors once its shares, already sent to the vault, were its last.
+pub fn record_withdrawal(
+    deps: DepsMut,
+    address: &Addr,
+    amount: Uint128,
+    strategy_denom: &str,
+) -> StdResult<()> {
+    let mut totals = user_totals(deps.storage, address)?;
+    totals.withdrawn += amount;
+    USER_TOTALS.save(deps.storage, address, &totals)?;
+
+    let shares = deps.querier.query_balance(address, strategy_denom)?.amount;
+    if shares.is_zero() {
+        DEPOSITORS.remove(deps.storage, address);
+    }
+
+    Ok(())
+}
+
+pub fn depositors(
+    deps: Deps,
+    strategy_denom: &str,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Depositor>> {
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    DEPOSITORS
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|address| {
+            let address = address?;
+            let shares = deps.querier.query_balance(&address, strategy_denom)?.amount;
+            Ok(Depositor { address, shares })
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+use crate::config::MyConfig;
+use crate::lockup::total_unlocking;
+use cosmwasm_std::{Decimal, Deps, Env, StdError, Uint128};
+use vaultenator::errors::ContractError;
+
+/// Virtual shares and assets added to both sides of the exchange rate so that
+/// donations made directly to the contract cannot inflate the share price
+/// enough to round later deposits down. Strategy tokens carry six more
+/// decimals than `base_denom`, so rounding a deposit down by a share costs an
+/// attacker a million times what the depositor loses.
+pub const VIRTUAL_SHARES: Uint128 = Uint128::new(1_000_000);
+pub const VIRTUAL_ASSETS: Uint128 = Uint128::one();
+
+/// Amount of `base_denom` held on the contract, excluding base tokens owed
+/// to unlocking positions.
+pub fn idle_assets(deps: Deps, env: &Env, config: &MyConfig) -> Result<Uint128, ContractError> {
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.base_denom)?;
+
+    let idle_assets = balance
+        .amount
+        .checked_sub(total_unlocking(deps)?)
+        .map_err(StdError::from)?;
+
+    Ok(idle_assets)
+}
+
+/// Total value of the vault in `base_denom`, idle base tokens plus the value
+/// deployed to the strategy.
+pub fn total_assets(deps: Deps, env: &Env, config: &MyConfig) -> Result<Uint128, ContractError> {
+    let idle_assets = idle_assets(deps, env, config)?;
+
+    match &config.strategy {
+        Some(strategy) => Ok(idle_assets + strategy.value(deps, env, &config.base_denom)?),
+        None => Ok(idle_assets),
+    }
+}
+
+/// Total supply of the strategy token as reported by the bank module.
+pub fn total_supply(deps: Deps, strategy_denom: &str) -> Result<Uint128, ContractError> {
+    let supply = deps.querier.query_supply(strategy_denom)?;
+
+    Ok(supply.amount)
+}
+
+/// Strategy tokens minted for a deposit of `assets`, rounded down in favour
+/// of the vault. The first deposit is minted `VIRTUAL_SHARES` strategy tokens
+/// per base token.
+pub fn convert_to_shares(assets: Uint128, total_assets: Uint128, total_supply: Uint128) -> Uint128 {
+    assets.multiply_ratio(total_supply + VIRTUAL_SHARES, total_assets + VIRTUAL_ASSETS)
+}
+
+/// Base tokens paid out for redeeming `shares`, rounded down in favour of the
+/// vault.
+pub fn convert_to_assets(shares: Uint128, total_assets: Uint128, total_supply: Uint128) -> Uint128 {
+    shares.multiply_ratio(total_assets + VIRTUAL_ASSETS, total_supply + VIRTUAL_SHARES)
+}
+
+/// Base tokens backing a single strategy token, including the virtual offsets.
+pub fn share_price(total_assets: Uint128, total_supply: Uint128) -> Decimal {
+    Decimal::from_ratio(total_assets + VIRTUAL_ASSETS, total_supply + VIRTUAL_SHARES)
+}
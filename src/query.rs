@@ -0,0 +1,209 @@
+use crate::config::MyConfig;
+use crate::contract::MyVault;
+use crate::fees::{pending_fees, split_fee};
+use crate::msg::{MyVaultExtensionQueryMsg, PendingFeesResponse, QueryMsg, UserPositionResponse};
+use crate::pause::pause_history;
+use crate::positions::{depositors, user_totals, Depositor};
+use crate::pricing::{convert_to_assets, convert_to_shares, total_assets, total_supply};
+use crate::roles::{has_role, role_members};
+use crate::share_price::{apr, share_price_history};
+use crate::state::MyState;
+use crate::timelock::pending_config_changes;
+use cosmwasm_std::{to_json_binary, Addr, Binary, Deps, Env, Int128, StdError, StdResult, Uint128};
+use cw_vault_standard::VaultInfoResponse;
+use vaultenator::config::Configure;
+use vaultenator::state::{ManageState, OWNER};
+
+impl MyVault {
+    pub fn query_extension(
+        &self,
+        deps: Deps,
+        env: Env,
+        msg: MyVaultExtensionQueryMsg,
+    ) -> StdResult<Binary> {
+        match msg {
+            MyVaultExtensionQueryMsg::RemainingCapacity {} => {
+                to_json_binary(&Self::query_remaining_capacity(deps, &env)?)
+            }
+            MyVaultExtensionQueryMsg::PendingFees {} => {
+                to_json_binary(&Self::query_pending_fees(deps, &env)?)
+            }
+            MyVaultExtensionQueryMsg::HasRole { role, address } => {
+                let address = deps.api.addr_validate(&address)?;
+                to_json_binary(
+                    &(OWNER.is_admin(deps, &address)? || has_role(deps, role, &address)?),
+                )
+            }
+            MyVaultExtensionQueryMsg::RoleMembers {
+                role,
+                start_after,
+                limit,
+            } => to_json_binary(&role_members(deps, role, start_after, limit)?),
+            MyVaultExtensionQueryMsg::PauseHistory { start_after, limit } => {
+                to_json_binary(&pause_history(deps, start_after, limit)?)
+            }
+            MyVaultExtensionQueryMsg::PendingConfigChanges { start_after, limit } => {
+                to_json_binary(&pending_config_changes(deps, start_after, limit)?)
+            }
+            MyVaultExtensionQueryMsg::UserPosition { address } => {
+                let address = deps.api.addr_validate(&address)?;
+                to_json_binary(&Self::query_user_position(deps, &env, &address)?)
+            }
+            MyVaultExtensionQueryMsg::SharePriceHistory { start_after, limit } => {
+                to_json_binary(&share_price_history(deps, start_after, limit)?)
+            }
+            MyVaultExtensionQueryMsg::Apr { window_seconds } => {
+                to_json_binary(&apr(deps, window_seconds)?)
+            }
+            MyVaultExtensionQueryMsg::Depositors { start_after, limit } => {
+                to_json_binary(&Self::query_depositors(deps, start_after, limit)?)
+            }
+        }
+    }
+
+    /// Answers the vault standard queries Vaultenator leaves unimplemented,
+    /// pricing shares the same way `Deposit` and `Redeem` do.
+    pub fn query_vault_standard(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        let config =
+            MyConfig::get_from_storage(deps).map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        match msg {
+            QueryMsg::Info {} => to_json_binary(&VaultInfoResponse {
+                base_token: config.base_denom.clone(),
+                vault_token: config
+                    .strategy_denom
+                    .ok_or_else(|| StdError::generic_err("Strategy denom not initialised"))?,
+            }),
+            QueryMsg::PreviewDeposit { amount } => {
+                // Exact for vaults holding deposits idle, a strategy mints
+                // from the value it deployed so this is an estimate before
+                // slippage
+                let (total_assets, total_supply) = Self::query_totals(deps, &env, &config)?;
+                let (_, amount) = split_fee(amount, config.deposit_fee);
+                to_json_binary(&convert_to_shares(amount, total_assets, total_supply))
+            }
+            QueryMsg::PreviewRedeem { amount } => {
+                // Exact for base tokens held idle, exiting a strategy pays out
+                // what the pool returns so this is an estimate before slippage
+                let (total_assets, total_supply) = Self::query_totals(deps, &env, &config)?;
+                let assets = convert_to_assets(amount, total_assets, total_supply);
+                let (_, assets) = split_fee(assets, config.withdrawal_fee);
+                to_json_binary(&assets)
+            }
+            QueryMsg::TotalAssets {} => to_json_binary(&Self::query_totals(deps, &env, &config)?.0),
+            QueryMsg::TotalVaultTokenSupply {} => {
+                to_json_binary(&Self::query_totals(deps, &env, &config)?.1)
+            }
+            QueryMsg::ConvertToShares { amount } => {
+                let (total_assets, total_supply) = Self::query_totals(deps, &env, &config)?;
+                to_json_binary(&convert_to_shares(amount, total_assets, total_supply))
+            }
+            QueryMsg::ConvertToAssets { amount } => {
+                let (total_assets, total_supply) = Self::query_totals(deps, &env, &config)?;
+                to_json_binary(&convert_to_assets(amount, total_assets, total_supply))
+            }
+            _ => Err(StdError::generic_err("Query is not a vault standard query")),
+        }
+    }
+
+    /// Total assets and the total supply including fee shares that the next
+    /// deposit or redeem would mint first.
+    fn query_totals(deps: Deps, env: &Env, config: &MyConfig) -> StdResult<(Uint128, Uint128)> {
+        let state =
+            MyState::get_from_storage(deps).map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        let total_assets =
+            total_assets(deps, env, config).map_err(|e| StdError::generic_err(e.to_string()))?;
+        let total_supply = match &config.strategy_denom {
+            Some(strategy_denom) => total_supply(deps, strategy_denom)
+                .map_err(|e| StdError::generic_err(e.to_string()))?,
+            None => Uint128::zero(),
+        };
+
+        let (fees, _) = pending_fees(config, &state, total_assets, total_supply, env.block.time);
+
+        Ok((total_assets, total_supply + fees.total()))
+    }
+
+    fn query_remaining_capacity(deps: Deps, env: &Env) -> StdResult<Option<Uint128>> {
+        let config =
+            MyConfig::get_from_storage(deps).map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        let Some(strategy_cap) = config.strategy_cap else {
+            return Ok(None);
+        };
+
+        let total_assets =
+            total_assets(deps, env, &config).map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        Ok(Some(strategy_cap.saturating_sub(total_assets)))
+    }
+
+    /// Values the shares `address` holds at the `PreviewRedeem` price so the
+    /// profit or loss includes the withdrawal fee.
+    fn query_user_position(
+        deps: Deps,
+        env: &Env,
+        address: &Addr,
+    ) -> StdResult<UserPositionResponse> {
+        let config =
+            MyConfig::get_from_storage(deps).map_err(|e| StdError::generic_err(e.to_string()))?;
+        let totals = user_totals(deps.storage, address)?;
+
+        let shares = match &config.strategy_denom {
+            Some(strategy_denom) => deps.querier.query_balance(address, strategy_denom)?.amount,
+            None => Uint128::zero(),
+        };
+        let (total_assets, total_supply) = Self::query_totals(deps, env, &config)?;
+        let value = convert_to_assets(shares, total_assets, total_supply);
+        let (_, value) = split_fee(value, config.withdrawal_fee);
+
+        let pnl = Int128::try_from(value + totals.withdrawn)?
+            .checked_sub(Int128::try_from(totals.deposited)?)?;
+
+        Ok(UserPositionResponse {
+            shares,
+            value,
+            deposited: totals.deposited,
+            withdrawn: totals.withdrawn,
+            pnl,
+        })
+    }
+
+    fn query_depositors(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Depositor>> {
+        let config =
+            MyConfig::get_from_storage(deps).map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        match &config.strategy_denom {
+            Some(strategy_denom) => depositors(deps, strategy_denom, start_after, limit),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn query_pending_fees(deps: Deps, env: &Env) -> StdResult<PendingFeesResponse> {
+        let config =
+            MyConfig::get_from_storage(deps).map_err(|e| StdError::generic_err(e.to_string()))?;
+        let state =
+            MyState::get_from_storage(deps).map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        let total_assets =
+            total_assets(deps, env, &config).map_err(|e| StdError::generic_err(e.to_string()))?;
+        let total_supply = match &config.strategy_denom {
+            Some(strategy_denom) => total_supply(deps, strategy_denom)
+                .map_err(|e| StdError::generic_err(e.to_string()))?,
+            None => Uint128::zero(),
+        };
+
+        let (fees, _) = pending_fees(&config, &state, total_assets, total_supply, env.block.time);
+
+        Ok(PendingFeesResponse {
+            management_fee: fees.management_fee,
+            performance_fee: fees.performance_fee,
+            high_water_mark: state.high_water_mark,
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use crate::config::MyConfig;
+use crate::contract::MyVault;
+use crate::harvest::{self, HARVEST_CLAIM_REPLY_ID, HARVEST_SWAP_REPLY_ID};
+use cosmwasm_std::{DepsMut, Env, Reply, Response, SubMsgResult};
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgCreateDenomResponse;
+use vaultenator::config::Configure;
+use vaultenator::errors::ContractError;
+use vaultenator::reply::{ReplyHandler, CREATE_STRATEGY_DENOM_REPLY_ID};
+
+impl ReplyHandler<MyConfig> for MyVault {
+    fn handle_reply(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        match msg.id {
+            CREATE_STRATEGY_DENOM_REPLY_ID => {
+                let sub_msg_response: SubMsgResult = msg.result;
+                let response: MsgCreateDenomResponse = sub_msg_response.try_into()?;
+
+                let mut config = MyConfig::get_from_storage(deps.as_ref())?;
+                config.update_strategy_denom(response.new_token_denom.clone());
+                config.save_to_storage(&mut deps)?;
+
+                Ok(Response::new().add_attribute("strategy_denom", &response.new_token_denom))
+            }
+            HARVEST_CLAIM_REPLY_ID | HARVEST_SWAP_REPLY_ID => {
+                let config = MyConfig::get_from_storage(deps.as_ref())?;
+                Ok(harvest::handle_reply(deps, &env, &config, msg)?)
+            }
+            // Any other reply continues a strategy deposit or withdrawal
+            _ => {
+                let config = MyConfig::get_from_storage(deps.as_ref())?;
+                let strategy = config
+                    .strategy
+                    .as_ref()
+                    .ok_or(ContractError::InvalidReplyId)?;
+
+                Ok(strategy.handle_reply(deps, &env, &config, msg)?)
+            }
+        }
+    }
+}
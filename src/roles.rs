@@ -0,0 +1,149 @@
+use crate::contract::MyVault;
+use crate::errors::ContractError;
+use crate::lockup::{DEFAULT_LIMIT, MAX_LIMIT};
+use crate::msg::UpdateConfigMsg;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Deps, DepsMut, Empty, Event, MessageInfo, Order, Response, StdResult};
+use cw_storage_plus::{Bound, Map};
+use std::fmt;
+use vaultenator::state::OWNER;
+
+/// Permissions the owner can grant to other addresses. The owner holds every
+/// role implicitly and is the only one who can grant and revoke them.
+#[cw_serde]
+#[derive(Copy)]
+pub enum Role {
+    /// Update non-fee config fields
+    ConfigAdmin,
+    /// Open, pause and unpause the vault
+    Pauser,
+    /// Pause the vault in an incident, only pausers and the owner can
+    /// unpause or reopen it
+    Guardian,
+    /// Update fee rates and the fee recipient
+    FeeManager,
+    /// Harvest and rebalance the strategy
+    Strategist,
+    /// Run operational messages, harvest, rebalance and pause
+    Keeper,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::ConfigAdmin => "config_admin",
+            Role::Pauser => "pauser",
+            Role::Guardian => "guardian",
+            Role::FeeManager => "fee_manager",
+            Role::Strategist => "strategist",
+            Role::Keeper => "keeper",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Members of each role keyed by role and address
+pub const ROLE_MEMBERS: Map<(&str, &Addr), Empty> = Map::new("role_members");
+
+pub fn has_role(deps: Deps, role: Role, address: &Addr) -> StdResult<bool> {
+    Ok(ROLE_MEMBERS.has(deps.storage, (role.as_str(), address)))
+}
+
+/// Allows the owner and members of any of `roles`, anyone else is rejected
+/// as not the admin.
+pub fn assert_any_role(deps: Deps, address: &Addr, roles: &[Role]) -> Result<(), ContractError> {
+    for role in roles {
+        if has_role(deps, *role, address)? {
+            return Ok(());
+        }
+    }
+
+    Ok(OWNER.assert_admin(deps, address)?)
+}
+
+/// Fee changes need the fee manager role and any other change the config
+/// admin role, the owner can make any change.
+pub fn assert_can_update_config(
+    deps: Deps,
+    address: &Addr,
+    msg: &UpdateConfigMsg,
+) -> Result<(), ContractError> {
+    if msg.changes_fees() {
+        assert_any_role(deps, address, &[Role::FeeManager])?;
+    }
+    if msg.changes_settings() || !msg.changes_fees() {
+        assert_any_role(deps, address, &[Role::ConfigAdmin])?;
+    }
+
+    Ok(())
+}
+
+pub fn role_members(
+    deps: Deps,
+    role: Role,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    ROLE_MEMBERS
+        .prefix(role.as_str())
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
+impl MyVault {
+    pub(crate) fn grant_role(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        role: Role,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        OWNER.assert_admin(deps.as_ref(), &info.sender)?;
+
+        let address = deps.api.addr_validate(&address)?;
+        ROLE_MEMBERS.save(deps.storage, (role.as_str(), &address), &Empty {})?;
+
+        let event = Event::new("grant_role").add_attributes(vec![
+            ("role", role.to_string()),
+            ("address", address.to_string()),
+        ]);
+
+        Ok(Response::new().add_event(event))
+    }
+
+    pub(crate) fn revoke_role(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        role: Role,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        OWNER.assert_admin(deps.as_ref(), &info.sender)?;
+
+        let address = deps.api.addr_validate(&address)?;
+        ROLE_MEMBERS.remove(deps.storage, (role.as_str(), &address));
+
+        let event = Event::new("revoke_role").add_attributes(vec![
+            ("role", role.to_string()),
+            ("address", address.to_string()),
+        ]);
+
+        Ok(Response::new().add_event(event))
+    }
+}
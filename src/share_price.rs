@@ -0,0 +1,153 @@
+use crate::config::MyConfig;
+use crate::errors::ContractError;
+use crate::fees::{pending_fees, SECONDS_PER_YEAR};
+use crate::lockup::{DEFAULT_LIMIT, MAX_LIMIT};
+use crate::msg::AprResponse;
+use crate::pricing::{share_price, total_assets, total_supply};
+use crate::state::MyState;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Deps, DepsMut, Env, Order, SignedDecimal, StdError, StdResult, Storage, Timestamp, Uint128,
+};
+use cw_storage_plus::{Bound, Item, Map};
+use vaultenator::state::ManageState;
+
+/// Number of share price snapshots kept, older snapshots are pruned
+pub const MAX_SHARE_PRICE_SNAPSHOTS: u64 = 365;
+
+/// Vault totals at the start of a deposit, redeem, unlock, emergency unlock,
+/// rebalance or fee accrual and once a harvest's rewards are swapped, after fee
+/// shares owed up to that point.
+#[cw_serde]
+pub struct SharePriceSnapshot {
+    pub id: u64,
+    pub timestamp: Timestamp,
+    pub total_assets: Uint128,
+    pub total_supply: Uint128,
+}
+
+pub const NEXT_SHARE_PRICE_SNAPSHOT_ID: Item<u64> = Item::new("next_share_price_snapshot_id");
+pub const SHARE_PRICE_HISTORY: Map<u64, SharePriceSnapshot> = Map::new("share_price_history");
+
+pub fn share_price_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<SharePriceSnapshot>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    SHARE_PRICE_HISTORY
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, snapshot)| snapshot))
+        .collect()
+}
+
+/// Appends a snapshot unless the latest was taken less than
+/// `share_price_snapshot_interval` seconds ago, dropping the oldest once more
+/// than `MAX_SHARE_PRICE_SNAPSHOTS` are held.
+pub fn record_share_price(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &MyConfig,
+    total_assets: Uint128,
+    total_supply: Uint128,
+) -> StdResult<()> {
+    let id = NEXT_SHARE_PRICE_SNAPSHOT_ID.may_load(storage)?.unwrap_or(1);
+
+    if let Some(latest) = SHARE_PRICE_HISTORY.may_load(storage, id - 1)? {
+        let next_snapshot_at = latest
+            .timestamp
+            .plus_seconds(config.share_price_snapshot_interval);
+        if config.share_price_snapshot_interval > 0 && env.block.time < next_snapshot_at {
+            return Ok(());
+        }
+    }
+
+    NEXT_SHARE_PRICE_SNAPSHOT_ID.save(storage, &(id + 1))?;
+    if id > MAX_SHARE_PRICE_SNAPSHOTS {
+        SHARE_PRICE_HISTORY.remove(storage, id - MAX_SHARE_PRICE_SNAPSHOTS);
+    }
+
+    SHARE_PRICE_HISTORY.save(
+        storage,
+        id,
+        &SharePriceSnapshot {
+            id,
+            timestamp: env.block.time,
+            total_assets,
+            total_supply,
+        },
+    )
+}
+
+/// Records the vault's current totals with `record_share_price`, counting the
+/// fee shares owed but not yet minted in the total supply.
+pub fn record_current_share_price(
+    deps: DepsMut,
+    env: &Env,
+    config: &MyConfig,
+) -> Result<(), ContractError> {
+    let state = MyState::get_from_storage(deps.as_ref())?;
+    let total_assets = total_assets(deps.as_ref(), env, config)?;
+    let total_supply = match &config.strategy_denom {
+        Some(strategy_denom) => total_supply(deps.as_ref(), strategy_denom)?,
+        None => Uint128::zero(),
+    };
+    let (fees, _) = pending_fees(config, &state, total_assets, total_supply, env.block.time);
+
+    Ok(record_share_price(
+        deps.storage,
+        env,
+        config,
+        total_assets,
+        total_supply + fees.total(),
+    )?)
+}
+
+/// Annualises the share price change between the latest snapshot and the
+/// oldest snapshot taken at most `window_seconds` before it.
+pub fn apr(deps: Deps, window_seconds: u64) -> StdResult<Option<AprResponse>> {
+    let mut snapshots = SHARE_PRICE_HISTORY.range(deps.storage, None, None, Order::Descending);
+
+    let Some(end) = snapshots.next().transpose()?.map(|(_, snapshot)| snapshot) else {
+        return Ok(None);
+    };
+    let window_start = end.timestamp.seconds().saturating_sub(window_seconds);
+
+    let mut start = end.clone();
+    for item in snapshots {
+        let (_, snapshot) = item?;
+        if snapshot.timestamp.seconds() < window_start {
+            break;
+        }
+        start = snapshot;
+    }
+
+    let elapsed = end.timestamp.seconds() - start.timestamp.seconds();
+    if elapsed == 0 {
+        return Ok(None);
+    }
+
+    let start_price = SignedDecimal::try_from(share_price(start.total_assets, start.total_supply))
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let end_price = SignedDecimal::try_from(share_price(end.total_assets, end.total_supply))
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    let apr = end_price
+        .checked_div(start_price)
+        .map_err(|e| StdError::generic_err(e.to_string()))?
+        .checked_sub(SignedDecimal::one())?
+        .checked_mul(SignedDecimal::from_ratio(SECONDS_PER_YEAR, elapsed))?;
+
+    Ok(Some(AprResponse {
+        apr,
+        start: start.timestamp,
+        end: end.timestamp,
+    }))
+}
@@ -1,12 +1,23 @@
+use crate::config::MyConfig;
+use crate::pricing::share_price;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Deps, DepsMut, Env, Timestamp};
-use vaultenator::{errors::ContractError, state::ManageState};
+use cosmwasm_std::{ensure, Decimal, Deps, DepsMut, Env, Timestamp, Uint128};
+use vaultenator::{config::Configure, errors::ContractError, state::ManageState};
 
 #[cw_serde]
 pub struct MyState {
     pub is_open: bool,
     pub is_paused: bool,
+    /// Time the vault was last paused
     pub last_pause: Timestamp,
+    /// Time the vault was last unpaused or opened, `None` until it is
+    pub last_unpause: Option<Timestamp>,
+    /// Time the management fee was last accrued
+    pub last_fee_accrual: Timestamp,
+    /// Highest share price the performance fee has been charged up to
+    pub high_water_mark: Decimal,
+    /// Concentrated liquidity position held by the vault, if any
+    pub position_id: Option<u64>,
 }
 
 impl ManageState for MyState {
@@ -35,12 +46,45 @@ impl ManageState for MyState {
             is_open: false,
             is_paused: true,
             last_pause: env.block.time,
+            last_unpause: None,
+            last_fee_accrual: env.block.time,
+            high_water_mark: share_price(Uint128::zero(), Uint128::zero()),
+            position_id: None,
         };
         initial_state.save_to_storage(deps)
     }
 
     fn update_state(&mut self, deps: &mut DepsMut) -> Result<(), ContractError> {
-        self.is_paused = !self.is_paused;
         self.save_to_storage(deps)
     }
+
+    // Vaultenator's default rejects vaults that are open or unpaused
+    fn is_open_and_unpaused(deps: Deps) -> Result<(), ContractError> {
+        let state = Self::get_from_storage(deps)?;
+        ensure!(state.is_open && !state.is_paused, ContractError::Paused {});
+        Ok(())
+    }
+}
+
+impl MyState {
+    /// Whether a pause has lasted longer than the configured maximum pause
+    /// duration, after which the vault is treated as unpaused.
+    pub fn is_pause_expired(&self, config: &MyConfig, env: &Env) -> bool {
+        config.max_pause_duration.is_some_and(|max_pause_duration| {
+            env.block.time >= self.last_pause.plus_seconds(max_pause_duration)
+        })
+    }
+
+    /// Checks the vault is open and either unpaused or paused for longer
+    /// than the maximum pause duration. Only exits use this, deposits and
+    /// investing stay blocked until the vault is unpaused.
+    pub fn assert_can_withdraw(deps: Deps, env: &Env) -> Result<(), ContractError> {
+        let state = Self::get_from_storage(deps)?;
+        let config = MyConfig::get_from_storage(deps)?;
+        ensure!(
+            state.is_open && (!state.is_paused || state.is_pause_expired(&config, env)),
+            ContractError::Paused {}
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,482 @@
+use crate::concentrated_liquidity::ConcentratedLiquidityStrategy;
+use crate::config::MyConfig;
+use crate::errors::ContractError;
+use crate::fees::{charge_fee, split_fee};
+use crate::gamm::GammStrategy;
+use crate::lockup::create_unlocking_position;
+use crate::msg::StrategyMsg;
+use crate::positions::record_withdrawal;
+use crate::pricing::{
+    convert_to_assets, convert_to_shares, idle_assets, share_price, total_assets, VIRTUAL_SHARES,
+};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coin, ensure, Addr, BankMsg, CosmosMsg, Decimal, Deps, DepsMut, Env, Event, Reply, Response,
+    StdResult, SubMsg, Uint128,
+};
+use cw_storage_plus::Item;
+use osmosis_std::shim::Timestamp as OsmosisTimestamp;
+use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgMint;
+use osmosis_std::types::osmosis::twap::v1beta1::ArithmeticTwapToNowRequest;
+use std::str::FromStr;
+use vaultenator::errors::ContractError as VaultenatorError;
+
+/// Seconds of pool history strategies price pair tokens over by default
+pub const DEFAULT_TWAP_WINDOW: u64 = 600;
+
+/// Share of the output the TWAP implies strategy swaps may lose by default
+pub const DEFAULT_MAX_SLIPPAGE: Decimal = Decimal::percent(5);
+
+/// Where deposited base tokens are deployed, held on the contract if unset.
+#[cw_serde]
+pub enum Strategy {
+    Gamm(GammStrategy),
+    ConcentratedLiquidity(ConcentratedLiquidityStrategy),
+}
+
+#[cw_serde]
+pub enum WithdrawalKind {
+    /// Pay the base tokens for `owner`'s shares out to `recipient`
+    Redeem { owner: Addr, recipient: Addr },
+    /// Lock the base tokens up in an unlocking position for `owner`
+    Unlock { owner: Addr },
+}
+
+impl WithdrawalKind {
+    /// Address whose shares are being withdrawn
+    pub fn owner(&self) -> &Addr {
+        match self {
+            WithdrawalKind::Redeem { owner, .. } | WithdrawalKind::Unlock { owner } => owner,
+        }
+    }
+}
+
+/// Withdrawal in progress while the strategy is being exited through
+/// submessage replies.
+#[cw_serde]
+pub struct PendingWithdrawal {
+    pub kind: WithdrawalKind,
+    /// Base tokens collected so far
+    pub base_amount: Uint128,
+    /// Pair tokens still to be swapped to base tokens
+    pub pair_amount: Uint128,
+    /// Strategy tokens burned for the withdrawal
+    pub shares: Uint128,
+    /// Share price the withdrawal was priced at
+    pub share_price: Decimal,
+}
+
+pub const PENDING_WITHDRAWAL: Item<PendingWithdrawal> = Item::new("pending_withdrawal");
+
+/// Deposit whose shares are minted once the strategy has deployed its base
+/// tokens, so swap costs are borne by the depositor.
+#[cw_serde]
+pub struct PendingDeposit {
+    pub sender: Addr,
+    pub recipient: Addr,
+    /// Base tokens deposited, including the deposit fee
+    pub amount: Uint128,
+    pub fee: Uint128,
+    /// Vault value before the deposit
+    pub total_assets: Uint128,
+    /// Strategy token supply before the deposit, including accrued fee shares
+    pub total_supply: Uint128,
+}
+
+pub const PENDING_DEPOSIT: Item<PendingDeposit> = Item::new("pending_deposit");
+
+impl Strategy {
+    pub fn new(deps: Deps, msg: StrategyMsg, base_denom: &str) -> Result<Self, ContractError> {
+        match msg {
+            StrategyMsg::Gamm {
+                pool_id,
+                twap_window,
+                max_slippage,
+            } => Ok(Strategy::Gamm(GammStrategy::new(
+                deps,
+                pool_id,
+                base_denom,
+                twap_window.unwrap_or(DEFAULT_TWAP_WINDOW),
+                max_slippage.unwrap_or(DEFAULT_MAX_SLIPPAGE),
+            )?)),
+            StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick,
+                upper_tick,
+                twap_window,
+                max_slippage,
+            } => Ok(Strategy::ConcentratedLiquidity(
+                ConcentratedLiquidityStrategy::new(
+                    deps,
+                    pool_id,
+                    base_denom,
+                    lower_tick,
+                    upper_tick,
+                    twap_window.unwrap_or(DEFAULT_TWAP_WINDOW),
+                    max_slippage.unwrap_or(DEFAULT_MAX_SLIPPAGE),
+                )?,
+            )),
+        }
+    }
+
+    /// Value in base tokens of everything deployed to the strategy.
+    pub fn value(
+        &self,
+        deps: Deps,
+        env: &Env,
+        base_denom: &str,
+    ) -> Result<Uint128, VaultenatorError> {
+        match self {
+            Strategy::Gamm(strategy) => Ok(strategy.value(deps, env, base_denom)?),
+            Strategy::ConcentratedLiquidity(strategy) => strategy.value(deps, env, base_denom),
+        }
+    }
+
+    /// Submessages deploying `amount` deposited base tokens.
+    pub fn invest(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        base_denom: &str,
+        amount: Uint128,
+    ) -> StdResult<Vec<SubMsg>> {
+        match self {
+            Strategy::Gamm(strategy) => strategy.invest(deps, env, base_denom, amount),
+            Strategy::ConcentratedLiquidity(strategy) => {
+                strategy.invest(deps, env, base_denom, amount)
+            }
+        }
+    }
+
+    /// Exits the share of the strategy owned by `shares` strategy tokens,
+    /// finishing the withdrawal once the last reply has been handled.
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw(
+        &self,
+        response: Response,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        withdrawal: PendingWithdrawal,
+        shares: Uint128,
+        total_supply: Uint128,
+    ) -> Result<Response, ContractError> {
+        match self {
+            Strategy::Gamm(strategy) => strategy.withdraw(
+                response,
+                deps,
+                env,
+                config,
+                withdrawal,
+                shares,
+                total_supply,
+            ),
+            Strategy::ConcentratedLiquidity(strategy) => strategy.withdraw(
+                response,
+                deps,
+                env,
+                config,
+                withdrawal,
+                shares,
+                total_supply,
+            ),
+        }
+    }
+
+    /// Denoms other than `base_denom` the strategy holds its value in.
+    pub fn asset_denoms(&self, base_denom: &str) -> Vec<String> {
+        match self {
+            Strategy::Gamm(strategy) => vec![strategy.pair_denom.clone(), strategy.lp_denom()],
+            Strategy::ConcentratedLiquidity(strategy) => {
+                vec![strategy.pair_denom(base_denom).to_string()]
+            }
+        }
+    }
+
+    /// TWAP window and maximum slippage the strategy's swaps are bounded by.
+    pub fn twap_params(&self) -> (u64, Decimal) {
+        match self {
+            Strategy::Gamm(strategy) => (strategy.twap_window, strategy.max_slippage),
+            Strategy::ConcentratedLiquidity(strategy) => {
+                (strategy.twap_window, strategy.max_slippage)
+            }
+        }
+    }
+
+    /// Messages claiming the rewards the strategy has earned, rewards that
+    /// accrue to the position itself need no claim.
+    pub fn claim_msgs(&self, deps: Deps, env: &Env) -> Result<Vec<CosmosMsg>, ContractError> {
+        match self {
+            // Swap fees accrue to the pool so LP shares compound by themselves
+            Strategy::Gamm(_) => Ok(vec![]),
+            Strategy::ConcentratedLiquidity(strategy) => strategy.claim_msgs(deps, env),
+        }
+    }
+
+    pub fn handle_reply(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        config: &MyConfig,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        match self {
+            Strategy::Gamm(strategy) => strategy.handle_reply(deps, env, config, msg),
+            Strategy::ConcentratedLiquidity(strategy) => {
+                strategy.handle_reply(deps, env, config, msg)
+            }
+        }
+    }
+}
+
+/// Deploys a deposit to the strategy if there is one, minting its shares once
+/// the last reply has been handled. Deposits the strategy cannot deploy are
+/// minted straight away.
+pub fn deposit(
+    response: Response,
+    mut deps: DepsMut,
+    env: &Env,
+    config: &MyConfig,
+    deposit: PendingDeposit,
+) -> Result<Response, ContractError> {
+    let amount = deposit.amount - deposit.fee;
+
+    let invest_msgs = match &config.strategy {
+        Some(strategy) => strategy.invest(deps.branch(), env, &config.base_denom, amount)?,
+        None => vec![],
+    };
+    if invest_msgs.is_empty() {
+        return mint_deposit(response, env, config, deposit, amount);
+    }
+
+    PENDING_DEPOSIT.save(deps.storage, &deposit)?;
+
+    Ok(response.add_submessages(invest_msgs))
+}
+
+/// Mints the shares of the pending deposit from the value it added to the
+/// vault. Harvests and rebalances deploy tokens through the same replies
+/// without a pending deposit, leaving the response untouched.
+pub fn finish_deposit(
+    response: Response,
+    deps: DepsMut,
+    env: &Env,
+    config: &MyConfig,
+) -> Result<Response, ContractError> {
+    let Some(deposit) = PENDING_DEPOSIT.may_load(deps.storage)? else {
+        return Ok(response);
+    };
+    PENDING_DEPOSIT.remove(deps.storage);
+
+    // The deposit fee has already been paid out so the vault only gained
+    // what the strategy deployed and left over
+    let value = total_assets(deps.as_ref(), env, config)?.saturating_sub(deposit.total_assets);
+
+    mint_deposit(response, env, config, deposit, value)
+}
+
+fn mint_deposit(
+    response: Response,
+    env: &Env,
+    config: &MyConfig,
+    deposit: PendingDeposit,
+    value: Uint128,
+) -> Result<Response, ContractError> {
+    let strategy_denom = config
+        .strategy_denom
+        .as_ref()
+        .ok_or(VaultenatorError::DenomNotInitialized {})?;
+
+    let shares = convert_to_shares(value, deposit.total_assets, deposit.total_supply);
+    ensure!(!shares.is_zero(), ContractError::ZeroShares {});
+
+    let msg = MsgMint {
+        sender: env.contract.address.to_string(),
+        amount: Some(OsmosisCoin {
+            denom: strategy_denom.to_string(),
+            amount: shares.to_string(),
+        }),
+        mint_to_address: deposit.recipient.to_string(),
+    };
+
+    let event = Event::new("deposit").add_attributes(vec![
+        ("sender", deposit.sender.to_string()),
+        ("recipient", deposit.recipient.to_string()),
+        ("amount", deposit.amount.to_string()),
+        ("net_amount", (deposit.amount - deposit.fee).to_string()),
+        ("shares", shares.to_string()),
+        (
+            "share_price",
+            share_price(deposit.total_assets, deposit.total_supply).to_string(),
+        ),
+        ("fee", deposit.fee.to_string()),
+    ]);
+
+    Ok(response.add_message(msg).add_event(event))
+}
+
+/// Withdraws the base tokens owned by `shares` strategy tokens, exiting the
+/// strategy first if there is one.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    response: Response,
+    deps: DepsMut,
+    env: &Env,
+    config: &MyConfig,
+    kind: WithdrawalKind,
+    shares: Uint128,
+    total_assets: Uint128,
+    total_supply: Uint128,
+) -> Result<Response, ContractError> {
+    let Some(strategy) = &config.strategy else {
+        let withdrawal = PendingWithdrawal {
+            kind,
+            base_amount: convert_to_assets(shares, total_assets, total_supply),
+            pair_amount: Uint128::zero(),
+            shares,
+            share_price: share_price(total_assets, total_supply),
+        };
+        return finish_withdrawal(response, deps, env, config, withdrawal);
+    };
+
+    let withdrawal = PendingWithdrawal {
+        kind,
+        base_amount: idle_assets(deps.as_ref(), env, config)?
+            .multiply_ratio(shares, total_supply + VIRTUAL_SHARES),
+        pair_amount: Uint128::zero(),
+        shares,
+        share_price: share_price(total_assets, total_supply),
+    };
+
+    strategy.withdraw(
+        response,
+        deps,
+        env,
+        config,
+        withdrawal,
+        shares,
+        total_supply,
+    )
+}
+
+/// Pays out or locks up the base tokens collected for a withdrawal, taking
+/// the withdrawal fee.
+pub fn finish_withdrawal(
+    response: Response,
+    mut deps: DepsMut,
+    env: &Env,
+    config: &MyConfig,
+    withdrawal: PendingWithdrawal,
+) -> Result<Response, ContractError> {
+    let (fee, base_amount) = split_fee(withdrawal.base_amount, config.withdrawal_fee);
+
+    let strategy_denom = config
+        .strategy_denom
+        .as_ref()
+        .ok_or(VaultenatorError::DenomNotInitialized {})?;
+    record_withdrawal(
+        deps.branch(),
+        withdrawal.kind.owner(),
+        base_amount,
+        strategy_denom,
+    )?;
+
+    let response = match withdrawal.kind {
+        WithdrawalKind::Redeem { owner, recipient } => {
+            let event = Event::new("redeem").add_attributes(vec![
+                ("sender", owner.to_string()),
+                ("recipient", recipient.to_string()),
+                ("amount", withdrawal.base_amount.to_string()),
+                ("net_amount", base_amount.to_string()),
+                ("shares", withdrawal.shares.to_string()),
+                ("share_price", withdrawal.share_price.to_string()),
+                ("fee", fee.to_string()),
+            ]);
+
+            response
+                .add_message(BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount: vec![coin(base_amount.into(), &config.base_denom)],
+                })
+                .add_event(event)
+        }
+        WithdrawalKind::Unlock { owner } => response.add_event(create_unlocking_position(
+            deps.storage,
+            env,
+            config,
+            owner,
+            base_amount,
+        )?),
+    };
+
+    Ok(charge_fee(response, config, "withdrawal", fee)?)
+}
+
+/// Arithmetic TWAP of `base_asset` in `quote_asset` over the last `window`
+/// seconds of `pool_id`, unlike the spot price it cannot be moved within a
+/// block.
+pub(crate) fn twap_price(
+    deps: Deps,
+    env: &Env,
+    pool_id: u64,
+    base_asset: &str,
+    quote_asset: &str,
+    window: u64,
+) -> StdResult<Decimal> {
+    let start_time = env.block.time.minus_seconds(window);
+
+    let twap = ArithmeticTwapToNowRequest {
+        pool_id,
+        base_asset: base_asset.to_string(),
+        quote_asset: quote_asset.to_string(),
+        start_time: Some(OsmosisTimestamp {
+            seconds: start_time.seconds() as i64,
+            nanos: start_time.subsec_nanos() as i32,
+        }),
+    }
+    .query(&deps.querier)?
+    .arithmetic_twap;
+
+    Decimal::from_str(&twap)
+}
+
+/// Least a swap of `amount` priced at `price` may return, swaps must ask for
+/// at least one token.
+pub(crate) fn min_amount_out(amount: Uint128, price: Decimal, max_slippage: Decimal) -> Uint128 {
+    amount
+        .mul_floor(price)
+        .mul_floor(Decimal::one() - max_slippage)
+        .max(Uint128::one())
+}
+
+/// Checks the TWAP window covers some history and swaps must return
+/// something.
+pub(crate) fn validate_twap_params(
+    twap_window: u64,
+    max_slippage: Decimal,
+) -> Result<(), ContractError> {
+    ensure!(twap_window > 0, ContractError::InvalidTwapWindow {});
+    ensure!(
+        max_slippage < Decimal::one(),
+        ContractError::InvalidMaxSlippage {}
+    );
+
+    Ok(())
+}
+
+/// Balance of `denom` held by the contract.
+pub(crate) fn query_balance(deps: Deps, env: &Env, denom: &str) -> StdResult<Uint128> {
+    Ok(deps
+        .querier
+        .query_balance(&env.contract.address, denom)?
+        .amount)
+}
+
+pub(crate) fn display_coins(coins: &[OsmosisCoin]) -> String {
+    coins
+        .iter()
+        .map(|coin| format!("{}{}", coin.amount, coin.denom))
+        .collect::<Vec<_>>()
+        .join(",")
+}
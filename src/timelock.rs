@@ -0,0 +1,130 @@
+use crate::config::MyConfig;
+use crate::contract::MyVault;
+use crate::errors::ContractError;
+use crate::lockup::{DEFAULT_LIMIT, MAX_LIMIT};
+use crate::msg::UpdateConfigMsg;
+use crate::roles::assert_can_update_config;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    ensure, Addr, Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult, Timestamp,
+};
+use cw_storage_plus::{Bound, Item, Map};
+use vaultenator::config::Configure;
+
+/// Longest config timelock that can be set, 30 days
+pub const MAX_CONFIG_TIMELOCK: u64 = 30 * 24 * 60 * 60;
+
+/// Config change queued by `ProposeConfigChange`.
+#[cw_serde]
+pub struct PendingConfigChange {
+    pub id: u64,
+    pub proposer: Addr,
+    pub msg: UpdateConfigMsg,
+    /// Earliest time the change can be executed
+    pub executable_at: Timestamp,
+}
+
+pub const NEXT_CONFIG_CHANGE_ID: Item<u64> = Item::new("next_config_change_id");
+pub const PENDING_CONFIG_CHANGES: Map<u64, PendingConfigChange> =
+    Map::new("pending_config_changes");
+
+pub fn pending_config_changes(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PendingConfigChange>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    PENDING_CONFIG_CHANGES
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, change)| change))
+        .collect()
+}
+
+fn load_pending_config_change(deps: Deps, id: u64) -> Result<PendingConfigChange, ContractError> {
+    PENDING_CONFIG_CHANGES
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::ConfigChangeNotFound { id })
+}
+
+impl MyVault {
+    pub(crate) fn propose_config_change(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: UpdateConfigMsg,
+    ) -> Result<Response, ContractError> {
+        assert_can_update_config(deps.as_ref(), &info.sender, &msg)?;
+
+        // Reject invalid changes now rather than once the timelock has passed
+        let config = MyConfig::get_from_storage(deps.as_ref())?;
+        config.clone().update(deps.api, msg.clone())?;
+
+        let id = NEXT_CONFIG_CHANGE_ID.may_load(deps.storage)?.unwrap_or(1);
+        NEXT_CONFIG_CHANGE_ID.save(deps.storage, &(id + 1))?;
+
+        let change = PendingConfigChange {
+            id,
+            proposer: info.sender,
+            msg,
+            executable_at: env.block.time.plus_seconds(config.config_timelock),
+        };
+        PENDING_CONFIG_CHANGES.save(deps.storage, id, &change)?;
+
+        let event = Event::new("propose_config_change").add_attributes(vec![
+            ("id", id.to_string()),
+            ("proposer", change.proposer.to_string()),
+            ("executable_at", change.executable_at.seconds().to_string()),
+        ]);
+
+        Ok(Response::new().add_event(event))
+    }
+
+    pub(crate) fn execute_config_change(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        id: u64,
+    ) -> Result<Response, ContractError> {
+        let change = load_pending_config_change(deps.as_ref(), id)?;
+        assert_can_update_config(deps.as_ref(), &info.sender, &change.msg)?;
+
+        ensure!(
+            env.block.time >= change.executable_at,
+            ContractError::ConfigChangeNotReady {
+                id,
+                executable_at: change.executable_at
+            }
+        );
+
+        PENDING_CONFIG_CHANGES.remove(deps.storage, id);
+
+        let response = Self::apply_config_update(deps, &env, change.msg)?;
+
+        Ok(response
+            .add_event(Event::new("execute_config_change").add_attribute("id", id.to_string())))
+    }
+
+    pub(crate) fn cancel_config_change(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        id: u64,
+    ) -> Result<Response, ContractError> {
+        let change = load_pending_config_change(deps.as_ref(), id)?;
+        assert_can_update_config(deps.as_ref(), &info.sender, &change.msg)?;
+
+        PENDING_CONFIG_CHANGES.remove(deps.storage, id);
+
+        Ok(Response::new()
+            .add_event(Event::new("cancel_config_change").add_attribute("id", id.to_string())))
+    }
+}
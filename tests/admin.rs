@@ -1,10 +1,15 @@
 mod helpers;
 extern crate example_vault;
+use cosmwasm_std::{Decimal, Uint128};
 use cw_controllers::AdminError;
-use helpers::helpers::{assert_err, contains_event};
+use cw_utils::Duration;
+use example_vault::errors::ContractError as MyContractError;
+use example_vault::msg::{InstantiateMsg, UpdateConfigMsg};
+use helpers::helpers::{assert_err, contains_event, contains_event_with_attributes, store_code};
 use helpers::setup::TestEnv;
-use osmosis_test_tube::{Module, Wasm};
+use osmosis_test_tube::{Account, Module, Wasm};
 use vaultenator::errors::ContractError;
+use vaultenator::msg::{ExecuteMsg, ExtensionExecuteMsg, VaultenatorExtensionExecuteMsg};
 
 #[test]
 fn set_open() {
@@ -145,3 +150,261 @@ fn set_unpause_contract_is_already_unpaused() {
 
     assert_err(res_err, ContractError::NotPaused {});
 }
+
+#[test]
+fn update_config() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let res = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                strategy_cap: Some(Some(Uint128::from(30_000_000u128))),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.strategy_cap, Some(Uint128::from(30_000_000u128)));
+    assert!(contains_event_with_attributes(
+        &res,
+        "update_config",
+        vec![
+            ("old_strategy_cap", "none"),
+            ("new_strategy_cap", "30000000"),
+        ]
+    ));
+
+    // Omitted fields are left unchanged
+    env.update_config(
+        &wasm,
+        &contract_addr,
+        UpdateConfigMsg::default(),
+        &env.signer,
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.strategy_cap, Some(Uint128::from(30_000_000u128)));
+
+    // Null removes the cap
+    let res = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                strategy_cap: Some(None),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.strategy_cap, None);
+    assert!(contains_event_with_attributes(
+        &res,
+        "update_config",
+        vec![
+            ("old_strategy_cap", "30000000"),
+            ("new_strategy_cap", "none"),
+        ]
+    ));
+}
+
+#[test]
+fn update_config_lockup_duration() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let res = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                lockup_duration: Some(Duration::Time(86400)),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.lockup_duration, Duration::Time(86400));
+    assert!(contains_event_with_attributes(
+        &res,
+        "update_config",
+        vec![
+            ("old_lockup_duration", "time: 0"),
+            ("new_lockup_duration", "time: 86400"),
+        ]
+    ));
+}
+
+#[test]
+fn update_config_not_admin() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let res_err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                strategy_cap: Some(Some(Uint128::from(30_000_000u128))),
+                ..Default::default()
+            },
+            &env.traders[0],
+        )
+        .unwrap_err();
+
+    assert_err(res_err, ContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn update_config_zero_strategy_cap() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let res_err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                strategy_cap: Some(Some(Uint128::zero())),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap_err();
+
+    assert_err(res_err, MyContractError::InvalidStrategyCap {});
+}
+
+#[test]
+fn instantiate_zero_strategy_cap() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let code_id = store_code(&wasm, &env.signer, env!("CARGO_PKG_NAME")).unwrap();
+
+    let msg = InstantiateMsg {
+        strategy_cap: Some(Uint128::zero()),
+        ..env.instantiate_msg()
+    };
+    let res_err = wasm
+        .instantiate(code_id, &msg, None, Some("example-vault"), &[], &env.signer)
+        .unwrap_err();
+
+    assert_err(res_err, MyContractError::InvalidStrategyCap {});
+}
+
+#[test]
+fn update_config_fees() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let fee_recipient = env.traders[5].address();
+    let res = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                deposit_fee: Some(Decimal::percent(1)),
+                performance_fee: Some(Decimal::percent(20)),
+                fee_recipient: Some(Some(fee_recipient.clone())),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.deposit_fee, Decimal::percent(1));
+    assert_eq!(config.withdrawal_fee, Decimal::zero());
+    assert_eq!(config.performance_fee, Decimal::percent(20));
+    assert_eq!(config.fee_recipient.unwrap().to_string(), fee_recipient);
+    assert!(contains_event_with_attributes(
+        &res,
+        "update_config",
+        vec![
+            ("old_deposit_fee", "0"),
+            ("new_deposit_fee", "0.01"),
+            ("old_fee_recipient", "none"),
+            ("new_fee_recipient", &fee_recipient),
+        ]
+    ));
+}
+
+#[test]
+fn update_config_fee_too_high() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let res_err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                withdrawal_fee: Some(Decimal::percent(6)),
+                fee_recipient: Some(Some(env.traders[5].address())),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap_err();
+
+    assert_err(
+        res_err,
+        MyContractError::FeeTooHigh {
+            name: "withdrawal_fee".to_string(),
+            max: Decimal::percent(5),
+        },
+    );
+}
+
+#[test]
+fn update_config_fees_without_recipient() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let res_err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                deposit_fee: Some(Decimal::percent(1)),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap_err();
+
+    assert_err(res_err, MyContractError::MissingFeeRecipient {});
+}
+
+#[test]
+fn update_config_without_payload() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let msg = ExecuteMsg::VaultExtension(ExtensionExecuteMsg::Vaultenator(
+        VaultenatorExtensionExecuteMsg::UpdateConfig {},
+    ));
+    let res_err = wasm
+        .execute(&contract_addr, &msg, &[], &env.signer)
+        .unwrap_err();
+
+    assert_err(res_err, ContractError::InvalidMessage {});
+}
@@ -0,0 +1,420 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Uint128};
+use cw_controllers::AdminError;
+use example_vault::concentrated_liquidity::{MAX_TICK, MIN_TICK};
+use example_vault::errors::ContractError;
+use example_vault::msg::{InstantiateMsg, StrategyMsg};
+use example_vault::roles::Role;
+use example_vault::strategy::Strategy;
+use helpers::helpers::{assert_err, contains_event, contains_event_with_attributes};
+use helpers::setup::{TestEnv, CL_LOWER_TICK, CL_UPPER_TICK};
+use osmosis_test_tube::{Account, Module, Wasm};
+use vaultenator::errors::ContractError as VaultenatorError;
+
+#[test]
+fn deposit_creates_and_adds_to_position() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let res = env
+        .deposit(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            &env.traders[0],
+        )
+        .unwrap();
+    assert!(contains_event_with_attributes(
+        &res,
+        "create_position",
+        vec![
+            ("lower_tick", &CL_LOWER_TICK.to_string()),
+            ("upper_tick", &CL_UPPER_TICK.to_string()),
+        ],
+    ));
+
+    let position_id = env
+        .query_state(&wasm, &contract_addr)
+        .unwrap()
+        .position_id
+        .unwrap();
+
+    let res = env
+        .deposit(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            &env.traders[1],
+        )
+        .unwrap();
+    assert!(contains_event(&res, "add_to_position"));
+
+    // Adding to a position replaces it
+    let state = env.query_state(&wasm, &contract_addr).unwrap();
+    assert!(state.position_id.unwrap() > position_id);
+}
+
+#[test]
+fn redeem_withdraws_from_position() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+    let shares = env.get_balance(&env.traders[0].address(), &strategy_denom);
+    let recipient = env.traders[1].address();
+    let recipient_balance = env.get_balance(&recipient, "uosmo");
+
+    let res = env
+        .redeem_to(
+            &wasm,
+            &contract_addr,
+            coin(shares.u128(), &strategy_denom),
+            Some(recipient.clone()),
+            &env.traders[0],
+        )
+        .unwrap();
+    assert!(contains_event(&res, "withdraw_position"));
+
+    // The pool charges no spread so only price impact and rounding are lost
+    let received = env.get_balance(&recipient, "uosmo") - recipient_balance;
+    assert!(received > Uint128::from(19_900_000u128));
+    assert!(received <= Uint128::from(20_000_000u128));
+    assert_eq!(env.get_total_supply(&strategy_denom), Uint128::zero());
+}
+
+#[test]
+fn value_ignores_spot_price() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    let total_assets = env.query_total_assets(&wasm, &contract_addr).unwrap();
+
+    // Doubles the spot price of uion, pushing it above the vault's range
+    env.swap(
+        pool_id,
+        coin(1_414_000_000_000u128, "uosmo"),
+        "uion",
+        &env.traders[2],
+    )
+    .unwrap();
+
+    let moved = env.query_total_assets(&wasm, &contract_addr).unwrap();
+    assert!(moved.abs_diff(total_assets) < total_assets.multiply_ratio(1u128, 50u128));
+
+    // Swapping at the moved price returns far less than the TWAP implies
+    let res_err = env
+        .deposit(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            &env.traders[1],
+        )
+        .unwrap_err();
+    assert!(res_err.to_string().contains("min amount"));
+}
+
+#[test]
+fn rebalance() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    let position_id = env
+        .query_state(&wasm, &contract_addr)
+        .unwrap()
+        .position_id
+        .unwrap();
+
+    let res = env
+        .rebalance(&wasm, &contract_addr, -200_000, 200_000, &env.signer)
+        .unwrap();
+    assert!(contains_event_with_attributes(
+        &res,
+        "rebalance",
+        vec![
+            ("old_lower_tick", "-100000"),
+            ("old_upper_tick", "100000"),
+            ("new_lower_tick", "-200000"),
+            ("new_upper_tick", "200000"),
+        ],
+    ));
+    assert!(contains_event_with_attributes(
+        &res,
+        "create_position",
+        vec![("lower_tick", "-200000"), ("upper_tick", "200000")],
+    ));
+
+    let state = env.query_state(&wasm, &contract_addr).unwrap();
+    assert_ne!(state.position_id.unwrap(), position_id);
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    match config.strategy {
+        Some(Strategy::ConcentratedLiquidity(strategy)) => {
+            assert_eq!(strategy.lower_tick, -200_000);
+            assert_eq!(strategy.upper_tick, 200_000);
+        }
+        _ => panic!("Expected a concentrated liquidity strategy"),
+    }
+}
+
+#[test]
+fn rebalance_by_keeper() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let keeper = &env.traders[9];
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        keeper.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    let res = env
+        .rebalance(&wasm, &contract_addr, -200_000, 200_000, keeper)
+        .unwrap();
+    assert!(contains_event(&res, "rebalance"));
+}
+
+#[test]
+fn rebalance_not_owner() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let err = env
+        .rebalance(&wasm, &contract_addr, -200_000, 200_000, &env.traders[0])
+        .unwrap_err();
+
+    assert_err(err, ContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn rebalance_while_paused() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    env.set_pause(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let err = env
+        .rebalance(&wasm, &contract_addr, -200_000, 200_000, &env.signer)
+        .unwrap_err();
+
+    assert_err(err, VaultenatorError::Paused {});
+}
+
+#[test]
+fn rebalance_invalid_range() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    for (lower_tick, upper_tick) in [
+        (200_000, -200_000),
+        (-200_050, 200_000),
+        (MIN_TICK - 100, 200_000),
+        (-200_000, MAX_TICK + 100),
+    ] {
+        let err = env
+            .rebalance(&wasm, &contract_addr, lower_tick, upper_tick, &env.signer)
+            .unwrap_err();
+
+        assert_err(
+            err,
+            ContractError::InvalidTickRange {
+                lower_tick,
+                upper_tick,
+            },
+        );
+    }
+}
+
+#[test]
+fn rebalance_to_min_tick() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: CL_LOWER_TICK,
+                upper_tick: CL_UPPER_TICK,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    env.rebalance(&wasm, &contract_addr, MIN_TICK, CL_UPPER_TICK, &env.signer)
+        .unwrap();
+
+    // The position is still valued with the lowest tick's price
+    let total_assets = env.query_total_assets(&wasm, &contract_addr).unwrap();
+    assert!(total_assets > Uint128::from(19_000_000u128));
+}
+
+#[test]
+fn rebalance_without_concentrated_liquidity_strategy() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    let err = env
+        .rebalance(&wasm, &contract_addr, -200_000, 200_000, &env.signer)
+        .unwrap_err();
+
+    assert_err(err, ContractError::NoConcentratedLiquidityStrategy {});
+}
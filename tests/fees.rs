@@ -0,0 +1,202 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Decimal, Uint128};
+use example_vault::fees::SECONDS_PER_YEAR;
+use example_vault::msg::InstantiateMsg;
+use helpers::helpers::{contains_event, contains_event_with_attributes};
+use helpers::setup::TestEnv;
+use osmosis_test_tube::{Account, Module, Wasm};
+
+#[test]
+fn accrue_management_fee() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            management_fee: Some(Decimal::percent(2)),
+            fee_recipient: Some(env.traders[5].address()),
+            ..env.instantiate_msg()
+        },
+    );
+    let fee_recipient = env.traders[5].address();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    // Nothing accrues before there are any shares
+    let pending_fees = env.query_pending_fees(&wasm, &contract_addr).unwrap();
+    assert_eq!(pending_fees.management_fee, Uint128::zero());
+
+    env.app.increase_time(SECONDS_PER_YEAR / 2);
+
+    // Half of the 2% annual fee, block times drift by a few seconds
+    let pending_fees = env.query_pending_fees(&wasm, &contract_addr).unwrap();
+    assert!(pending_fees.management_fee >= Uint128::from(200_000_000_000u128));
+    assert!(pending_fees.management_fee < Uint128::from(200_010_000_000u128));
+
+    let res = env
+        .accrue_fees(&wasm, &contract_addr, &env.traders[1])
+        .unwrap();
+    assert!(contains_event_with_attributes(
+        &res,
+        "fee",
+        vec![("fee_type", "management"), ("recipient", &fee_recipient)]
+    ));
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    let fee_shares = env.get_balance(&fee_recipient, &strategy_denom);
+    assert!(fee_shares >= Uint128::from(200_000_000_000u128));
+    assert!(fee_shares < Uint128::from(200_010_000_000u128));
+
+    let pending_fees = env.query_pending_fees(&wasm, &contract_addr).unwrap();
+    assert!(pending_fees.management_fee < Uint128::from(10_000_000u128));
+}
+
+#[test]
+fn management_fee_dilutes_redemptions() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            management_fee: Some(Decimal::percent(2)),
+            fee_recipient: Some(env.traders[5].address()),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    env.app.increase_time(SECONDS_PER_YEAR);
+
+    // Fee shares are minted within the redemption and priced in before payout
+    env.redeem(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000_000_000u128, strategy_denom.clone()),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let fee_shares = env.get_balance(&env.traders[5].address(), &strategy_denom);
+    assert!(fee_shares >= Uint128::from(400_000_000_000u128));
+
+    // The remaining base tokens back the fee recipient's shares
+    let contract_balance = env.get_balance(&contract_addr, "uosmo");
+    assert!(contract_balance >= Uint128::from(390_000u128));
+    assert!(contract_balance < Uint128::from(400_000u128));
+}
+
+#[test]
+fn accrue_fees_without_management_fee() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    env.app.increase_time(SECONDS_PER_YEAR);
+
+    let res = env
+        .accrue_fees(&wasm, &contract_addr, &env.traders[1])
+        .unwrap();
+    assert!(!contains_event(&res, "fee"));
+}
+
+#[test]
+fn performance_fee_above_high_water_mark() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            performance_fee: Some(Decimal::percent(20)),
+            fee_recipient: Some(env.traders[5].address()),
+            ..env.instantiate_msg()
+        },
+    );
+    let fee_recipient = env.traders[5].address();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let pending_fees = env.query_pending_fees(&wasm, &contract_addr).unwrap();
+    assert_eq!(pending_fees.performance_fee, Uint128::zero());
+    assert_eq!(
+        pending_fees.high_water_mark,
+        Decimal::from_ratio(1u128, 1_000_000u128)
+    );
+
+    // Yield doubles the share price
+    env.send(&contract_addr, coin(10_000_000u128, "uosmo"), &env.signer)
+        .unwrap();
+
+    // 20% of the 10_000_000 gain is paid in shares worth 2_000_000
+    let pending_fees = env.query_pending_fees(&wasm, &contract_addr).unwrap();
+    assert!(pending_fees.performance_fee > Uint128::from(1_111_000_000_000u128));
+    assert!(pending_fees.performance_fee < Uint128::from(1_111_200_000_000u128));
+
+    let res = env
+        .accrue_fees(&wasm, &contract_addr, &env.traders[1])
+        .unwrap();
+    assert!(contains_event_with_attributes(
+        &res,
+        "fee",
+        vec![
+            ("fee_type", "performance"),
+            ("recipient", &fee_recipient),
+            ("amount", &pending_fees.performance_fee.to_string()),
+        ]
+    ));
+
+    // The mark moves to the share price after dilution and no further fee
+    // is owed until it is exceeded
+    let pending_fees = env.query_pending_fees(&wasm, &contract_addr).unwrap();
+    assert_eq!(pending_fees.performance_fee, Uint128::zero());
+    assert!(pending_fees.high_water_mark > Decimal::from_ratio(179u128, 100_000_000u128));
+    assert!(pending_fees.high_water_mark < Decimal::from_ratio(181u128, 100_000_000u128));
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    env.redeem(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000_000_000u128, strategy_denom),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    // What is left backs the fee recipient's shares
+    let contract_balance = env.get_balance(&contract_addr, "uosmo");
+    assert!(contract_balance > Uint128::from(1_999_000u128));
+    assert!(contract_balance < Uint128::from(2_001_000u128));
+}
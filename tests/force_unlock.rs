@@ -0,0 +1,255 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Uint128};
+use cw_controllers::AdminError;
+use cw_utils::Duration;
+use example_vault::errors::ContractError as MyContractError;
+use example_vault::msg::InstantiateMsg;
+use helpers::helpers::{assert_err, contains_event_with_attributes};
+use helpers::setup::{TestEnv, LOCKUP_DURATION};
+use osmosis_test_tube::{Account, Module, Wasm};
+use vaultenator::errors::ContractError;
+
+#[test]
+fn update_force_withdraw_whitelist_not_admin() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    let res_err = env
+        .update_force_withdraw_whitelist(
+            &wasm,
+            &contract_addr,
+            vec![env.traders[0].address()],
+            vec![],
+            &env.traders[0],
+        )
+        .unwrap_err();
+
+    assert_err(res_err, ContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn force_redeem() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+            ..env.instantiate_msg()
+        },
+    );
+    let liquidator = &env.traders[9];
+    env.update_force_withdraw_whitelist(
+        &wasm,
+        &contract_addr,
+        vec![liquidator.address()],
+        vec![],
+        &env.signer,
+    )
+    .unwrap();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    // Collateral is seized by the liquidator
+    env.send(
+        &liquidator.address(),
+        coin(20_000_000_000_000u128, strategy_denom.clone()),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let res_err = env
+        .force_redeem(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000_000_000u128, strategy_denom.clone()),
+            None,
+            &env.traders[0],
+        )
+        .unwrap_err();
+    assert_err(res_err, ContractError::Unauthorized {});
+
+    let recipient = env.traders[1].address();
+    let res = env
+        .force_redeem(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000_000_000u128, strategy_denom.clone()),
+            Some(recipient.clone()),
+            liquidator,
+        )
+        .unwrap();
+
+    assert!(contains_event_with_attributes(
+        &res,
+        "force_redeem",
+        vec![("recipient", &recipient)]
+    ));
+    assert_eq!(
+        env.get_balance(&liquidator.address(), &strategy_denom),
+        Uint128::zero()
+    );
+    assert_eq!(env.get_balance(&contract_addr, "uosmo"), Uint128::zero());
+}
+
+#[test]
+fn force_redeem_removed_from_whitelist() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+            ..env.instantiate_msg()
+        },
+    );
+    let liquidator = &env.traders[9];
+    env.update_force_withdraw_whitelist(
+        &wasm,
+        &contract_addr,
+        vec![liquidator.address()],
+        vec![],
+        &env.signer,
+    )
+    .unwrap();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        liquidator,
+    )
+    .unwrap();
+
+    env.update_force_withdraw_whitelist(
+        &wasm,
+        &contract_addr,
+        vec![],
+        vec![liquidator.address()],
+        &env.signer,
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    let res_err = env
+        .force_redeem(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000_000_000u128, strategy_denom),
+            None,
+            liquidator,
+        )
+        .unwrap_err();
+    assert_err(res_err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn force_withdraw_unlocking() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+            ..env.instantiate_msg()
+        },
+    );
+    let liquidator = &env.traders[9];
+    env.update_force_withdraw_whitelist(
+        &wasm,
+        &contract_addr,
+        vec![liquidator.address()],
+        vec![],
+        &env.signer,
+    )
+    .unwrap();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    env.unlock(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000_000_000u128, strategy_denom),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let res_err = env
+        .force_withdraw_unlocking(&wasm, &contract_addr, 1, None, None, &env.traders[0])
+        .unwrap_err();
+    assert_err(res_err, ContractError::Unauthorized {});
+
+    let res_err = env
+        .force_withdraw_unlocking(
+            &wasm,
+            &contract_addr,
+            1,
+            Some(Uint128::from(20_000_001u128)),
+            None,
+            liquidator,
+        )
+        .unwrap_err();
+    assert_err(
+        res_err,
+        MyContractError::InvalidUnlockingAmount { lockup_id: 1 },
+    );
+
+    // Partial withdrawal leaves the remainder unlocking
+    let res = env
+        .force_withdraw_unlocking(
+            &wasm,
+            &contract_addr,
+            1,
+            Some(Uint128::from(5_000_000u128)),
+            None,
+            liquidator,
+        )
+        .unwrap();
+
+    assert!(contains_event_with_attributes(
+        &res,
+        "force_withdraw_unlocking",
+        vec![
+            ("lockup_id", "1"),
+            ("recipient", &liquidator.address()),
+            ("base_token_amount", "5000000"),
+        ]
+    ));
+
+    let position = env
+        .query_unlocking_position(&wasm, &contract_addr, 1)
+        .unwrap();
+    assert_eq!(position.base_token_amount, Uint128::from(15_000_000u128));
+
+    // Withdrawing without an amount empties the position
+    env.force_withdraw_unlocking(&wasm, &contract_addr, 1, None, None, liquidator)
+        .unwrap();
+
+    let res_err = env
+        .query_unlocking_position(&wasm, &contract_addr, 1)
+        .unwrap_err();
+    assert!(res_err.to_string().contains("not found"));
+    assert_eq!(env.get_balance(&contract_addr, "uosmo"), Uint128::zero());
+}
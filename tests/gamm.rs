@@ -0,0 +1,316 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Decimal, Uint128};
+use example_vault::errors::ContractError;
+use example_vault::msg::{InstantiateMsg, StrategyMsg};
+use helpers::helpers::{assert_err, contains_event, store_code};
+use helpers::setup::TestEnv;
+use osmosis_test_tube::{Account, Module, Wasm};
+
+#[test]
+fn deposit_joins_pool() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(100_000_000u128)),
+            strategy: Some(StrategyMsg::Gamm {
+                pool_id,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let res = env
+        .deposit(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            &env.traders[0],
+        )
+        .unwrap();
+    assert!(contains_event(&res, "join_pool"));
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    // Shares are minted from the value the deposit added once it joined the
+    // pool, so the depositor bears the 1% fee on the swapped half
+    let shares = env.get_balance(&env.traders[0].address(), &strategy_denom);
+    assert!(shares > Uint128::from(19_880_000_000_000u128));
+    assert!(shares < Uint128::from(19_920_000_000_000u128));
+    assert!(!env
+        .get_balance(&contract_addr, &format!("gamm/pool/{}", pool_id))
+        .is_zero());
+    assert!(env.get_balance(&contract_addr, "uosmo") < Uint128::from(200_000u128));
+
+    // The position is worth the deposit less the 1% fee on the swapped half
+    let remaining = env
+        .query_remaining_capacity(&wasm, &contract_addr)
+        .unwrap()
+        .unwrap();
+    assert!(remaining > Uint128::from(80_000_000u128));
+    assert!(remaining < Uint128::from(80_200_000u128));
+}
+
+#[test]
+fn redeem_exits_pool() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(100_000_000u128)),
+            strategy: Some(StrategyMsg::Gamm {
+                pool_id,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+    let lp_denom = format!("gamm/pool/{}", pool_id);
+    let lp_shares = env.get_balance(&contract_addr, &lp_denom);
+    let shares = env.get_balance(&env.traders[0].address(), &strategy_denom);
+    let recipient = env.traders[1].address();
+    let recipient_balance = env.get_balance(&recipient, "uosmo");
+
+    let res = env
+        .redeem_to(
+            &wasm,
+            &contract_addr,
+            coin(shares.u128(), &strategy_denom),
+            Some(recipient.clone()),
+            &env.traders[0],
+        )
+        .unwrap();
+    assert!(contains_event(&res, "exit_pool"));
+
+    // Both swaps take a 1% fee on roughly half the position
+    let received = env.get_balance(&recipient, "uosmo") - recipient_balance;
+    assert!(received > Uint128::from(19_700_000u128));
+    assert!(received < Uint128::from(20_000_000u128));
+
+    // Only the dust owned by the virtual shares is left in the pool
+    assert!(
+        env.get_balance(&contract_addr, &lp_denom) < lp_shares.multiply_ratio(1u128, 1_000_000u128)
+    );
+    assert!(env.get_balance(&contract_addr, "uion") < Uint128::from(2u128));
+    assert_eq!(env.get_total_supply(&strategy_denom), Uint128::zero());
+}
+
+#[test]
+fn deposit_swap_costs_stay_with_depositor() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(100_000_000u128)),
+            strategy: Some(StrategyMsg::Gamm {
+                pool_id,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    let shares = env.get_balance(&env.traders[0].address(), &strategy_denom);
+    let value = env
+        .query_convert_to_assets(&wasm, &contract_addr, shares)
+        .unwrap();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[1],
+    )
+    .unwrap();
+
+    // The second deposit's swap fee is not shared with the first depositor
+    assert!(
+        env.query_convert_to_assets(&wasm, &contract_addr, shares)
+            .unwrap()
+            >= value
+    );
+}
+
+#[test]
+fn value_ignores_spot_price() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(100_000_000u128)),
+            strategy: Some(StrategyMsg::Gamm {
+                pool_id,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    let total_assets = env.query_total_assets(&wasm, &contract_addr).unwrap();
+
+    // Doubles the spot price of uion in the pool
+    env.swap(
+        pool_id,
+        coin(414_000_000_000, "uosmo"),
+        "uion",
+        &env.traders[2],
+    )
+    .unwrap();
+
+    // Valued at the spot price the position would be worth 40% more
+    let moved = env.query_total_assets(&wasm, &contract_addr).unwrap();
+    assert!(moved.abs_diff(total_assets) < total_assets.multiply_ratio(1u128, 50u128));
+
+    // Swapping at the moved price returns far less than the TWAP implies
+    let res_err = env
+        .deposit(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            &env.traders[1],
+        )
+        .unwrap_err();
+    assert!(res_err.to_string().contains("min amount"));
+}
+
+#[test]
+fn instantiate_with_unequal_weights() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_weighted_pool();
+    let code_id = store_code(&wasm, &env.signer, env!("CARGO_PKG_NAME")).unwrap();
+
+    let msg = InstantiateMsg {
+        strategy: Some(StrategyMsg::Gamm {
+            pool_id,
+            twap_window: None,
+            max_slippage: None,
+        }),
+        ..env.instantiate_msg()
+    };
+    let err = wasm
+        .instantiate(code_id, &msg, None, Some("example-vault"), &[], &env.signer)
+        .unwrap_err();
+
+    assert_err(err, ContractError::UnequalPoolWeights { pool_id });
+}
+
+#[test]
+fn instantiate_with_invalid_twap_params() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let code_id = store_code(&wasm, &env.signer, env!("CARGO_PKG_NAME")).unwrap();
+
+    let msg = InstantiateMsg {
+        strategy: Some(StrategyMsg::Gamm {
+            pool_id,
+            twap_window: Some(0),
+            max_slippage: None,
+        }),
+        ..env.instantiate_msg()
+    };
+    let err = wasm
+        .instantiate(code_id, &msg, None, Some("example-vault"), &[], &env.signer)
+        .unwrap_err();
+    assert_err(err, ContractError::InvalidTwapWindow {});
+
+    let msg = InstantiateMsg {
+        strategy: Some(StrategyMsg::Gamm {
+            pool_id,
+            twap_window: None,
+            max_slippage: Some(Decimal::one()),
+        }),
+        ..env.instantiate_msg()
+    };
+    let err = wasm
+        .instantiate(code_id, &msg, None, Some("example-vault"), &[], &env.signer)
+        .unwrap_err();
+    assert_err(err, ContractError::InvalidMaxSlippage {});
+}
+
+#[test]
+fn instantiate_with_pool_missing_base_denom() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let code_id = store_code(&wasm, &env.signer, env!("CARGO_PKG_NAME")).unwrap();
+
+    let msg = InstantiateMsg {
+        base_denom: "uatom".to_string(),
+        strategy: Some(StrategyMsg::Gamm {
+            pool_id,
+            twap_window: None,
+            max_slippage: None,
+        }),
+        ..env.instantiate_msg()
+    };
+    let err = wasm
+        .instantiate(code_id, &msg, None, Some("example-vault"), &[], &env.signer)
+        .unwrap_err();
+
+    assert_err(err, ContractError::InvalidPool { pool_id });
+}
+
+#[test]
+fn instantiate_with_missing_pool() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let code_id = store_code(&wasm, &env.signer, env!("CARGO_PKG_NAME")).unwrap();
+
+    let msg = InstantiateMsg {
+        strategy: Some(StrategyMsg::Gamm {
+            pool_id: 99,
+            twap_window: None,
+            max_slippage: None,
+        }),
+        ..env.instantiate_msg()
+    };
+
+    assert!(wasm
+        .instantiate(code_id, &msg, None, Some("example-vault"), &[], &env.signer)
+        .is_err());
+}
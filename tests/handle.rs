@@ -1,15 +1,18 @@
 mod helpers;
 extern crate example_vault;
-use cosmwasm_std::{coin, Uint128};
-
+use cosmwasm_std::{coin, Decimal, Uint128};
+use example_vault::errors::ContractError;
+use example_vault::msg::{ExecuteMsg, InstantiateMsg};
+use helpers::helpers::{assert_err, contains_event_with_attributes};
 use helpers::setup::TestEnv;
 use osmosis_test_tube::{Account, Module, Wasm};
+use vaultenator::errors::ContractError as VaultenatorError;
 
 #[test]
 fn deposit() {
     let env = TestEnv::new();
     let wasm = Wasm::new(&env.app);
-    let contract_addr = env.deploy_contract(&wasm);
+    let contract_addr = env.deploy_open_contract(&wasm);
     let amount = coin(20_000_000u128, "uosmo".to_string());
     env.deposit(&wasm, &contract_addr, amount, &env.signer)
         .unwrap();
@@ -23,8 +26,14 @@ fn deposit() {
 
     let base_denom_total_supply = env.get_total_supply(&strategy_denom);
 
-    assert_eq!(base_denom_total_supply, Uint128::from(20_000_000u128));
-    assert_eq!(signer_strategy_denom_balance, Uint128::from(20_000_000u128));
+    assert_eq!(
+        base_denom_total_supply,
+        Uint128::from(20_000_000_000_000u128)
+    );
+    assert_eq!(
+        signer_strategy_denom_balance,
+        Uint128::from(20_000_000_000_000u128)
+    );
     assert_eq!(contract_base_denom_balance, Uint128::from(20_000_000u128));
 }
 
@@ -32,7 +41,7 @@ fn deposit() {
 fn redeem() {
     let env = TestEnv::new();
     let wasm = Wasm::new(&env.app);
-    let contract_addr = env.deploy_contract(&wasm);
+    let contract_addr = env.deploy_open_contract(&wasm);
 
     // Deposit 20_000_000
     let amount = coin(20_000_000u128, "uosmo".to_string());
@@ -46,12 +55,18 @@ fn redeem() {
     let contract_base_denom_balance = env.get_balance(&contract_addr, &config.base_denom);
     let strategy_denom_total_supply = env.get_total_supply(&strategy_denom);
 
-    assert_eq!(strategy_denom_total_supply, Uint128::from(20_000_000u128));
-    assert_eq!(signer_strategy_denom_balance, Uint128::from(20_000_000u128));
+    assert_eq!(
+        strategy_denom_total_supply,
+        Uint128::from(20_000_000_000_000u128)
+    );
+    assert_eq!(
+        signer_strategy_denom_balance,
+        Uint128::from(20_000_000_000_000u128)
+    );
     assert_eq!(contract_base_denom_balance, Uint128::from(20_000_000u128));
 
-    // Redeem 20_000_000
-    let strategy_token_amount = coin(20_000_000u128, strategy_denom.clone());
+    // Redeem 20_000_000_000_000
+    let strategy_token_amount = coin(20_000_000_000_000u128, strategy_denom.clone());
     env.redeem(&wasm, &contract_addr, strategy_token_amount, &env.signer)
         .unwrap();
     let signer_strategy_denom_balance = env.get_balance(&env.signer.address(), &strategy_denom);
@@ -60,3 +75,537 @@ fn redeem() {
     assert_eq!(signer_strategy_denom_balance, Uint128::from(0u128));
     assert_eq!(contract_base_denom_balance, Uint128::from(0u128));
 }
+
+#[test]
+fn deposit_requires_open_and_unpaused_vault() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let amount = coin(1_000_000u128, "uosmo");
+
+    let err = env
+        .deposit(&wasm, &contract_addr, amount.clone(), &env.signer)
+        .unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+
+    env.set_open(&wasm, &contract_addr, &env.signer).unwrap();
+    env.deposit(&wasm, &contract_addr, amount.clone(), &env.signer)
+        .unwrap();
+
+    env.set_pause(&wasm, &contract_addr, &env.signer).unwrap();
+    let err = env
+        .deposit(&wasm, &contract_addr, amount, &env.signer)
+        .unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+}
+
+#[test]
+fn deposit_after_yield() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    // Deposit 20_000_000 at the initial share price
+    let amount = coin(20_000_000u128, "uosmo".to_string());
+    env.deposit(&wasm, &contract_addr, amount, &env.signer)
+        .unwrap();
+
+    // Vault earns 20_000_000 so each share doubles in value
+    env.send(
+        &contract_addr,
+        coin(20_000_000u128, "uosmo".to_string()),
+        &env.signer,
+    )
+    .unwrap();
+
+    let amount = coin(20_000_000u128, "uosmo".to_string());
+    env.deposit(&wasm, &contract_addr, amount, &env.traders[0])
+        .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    let trader_strategy_denom_balance = env.get_balance(&env.traders[0].address(), &strategy_denom);
+    let contract_base_denom_balance = env.get_balance(&contract_addr, &config.base_denom);
+    let strategy_denom_total_supply = env.get_total_supply(&strategy_denom);
+
+    assert_eq!(
+        trader_strategy_denom_balance,
+        Uint128::from(10_000_000_249_999u128)
+    );
+    assert_eq!(contract_base_denom_balance, Uint128::from(60_000_000u128));
+    assert_eq!(
+        strategy_denom_total_supply,
+        Uint128::from(30_000_000_249_999u128)
+    );
+}
+
+#[test]
+fn redeem_after_yield() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    // Deposit 20_000_000 at the initial share price
+    let amount = coin(20_000_000u128, "uosmo".to_string());
+    env.deposit(&wasm, &contract_addr, amount, &env.traders[0])
+        .unwrap();
+
+    // Vault earns 10_000_000
+    env.send(
+        &contract_addr,
+        coin(10_000_000u128, "uosmo".to_string()),
+        &env.signer,
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    // Redeem half of the shares for half of the assets, less rounding in
+    // favour of the vault
+    let strategy_token_amount = coin(10_000_000_000_000u128, strategy_denom.clone());
+    env.redeem(
+        &wasm,
+        &contract_addr,
+        strategy_token_amount,
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let trader_strategy_denom_balance = env.get_balance(&env.traders[0].address(), &strategy_denom);
+    let contract_base_denom_balance = env.get_balance(&contract_addr, &config.base_denom);
+
+    assert_eq!(
+        trader_strategy_denom_balance,
+        Uint128::from(10_000_000_000_000u128)
+    );
+    assert_eq!(contract_base_denom_balance, Uint128::from(15_000_001u128));
+}
+
+#[test]
+fn deposit_after_donation_attack() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+    let attacker = &env.traders[0];
+    let victim = &env.traders[1];
+
+    // Attacker front-runs with a dust deposit then donates to the vault to
+    // inflate the share price
+    env.deposit(&wasm, &contract_addr, coin(1u128, "uosmo"), attacker)
+        .unwrap();
+    env.send(
+        &contract_addr,
+        coin(1_000_000_000_000u128, "uosmo"),
+        attacker,
+    )
+    .unwrap();
+
+    // A deposit that would round down to zero shares is rejected
+    let res_err = env
+        .deposit(&wasm, &contract_addr, coin(100_000u128, "uosmo"), victim)
+        .unwrap_err();
+    assert_err(res_err, ContractError::ZeroShares {});
+
+    // Each share is now worth about 500_000 base tokens, a deposit matching
+    // the donation only loses the fraction of a share rounded down
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(1_000_000_000_000u128, "uosmo"),
+        victim,
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+    let victim_shares = env.get_balance(&victim.address(), &strategy_denom);
+    assert_eq!(victim_shares, Uint128::from(1_999_999u128));
+
+    // Redeem to another account so gas does not count towards the loss
+    let recipient = env.traders[2].address();
+    let recipient_balance_before = env.get_balance(&recipient, "uosmo");
+    env.redeem_to(
+        &wasm,
+        &contract_addr,
+        coin(victim_shares.u128(), strategy_denom.clone()),
+        Some(recipient.clone()),
+        victim,
+    )
+    .unwrap();
+
+    let received = env.get_balance(&recipient, "uosmo") - recipient_balance_before;
+    assert_eq!(received, Uint128::from(999_999_750_000u128));
+    assert!(Uint128::from(1_000_000_000_000u128) - received < Uint128::from(500_000u128));
+
+    // The virtual shares capture half of the donation so the attacker
+    // redeems for far less than they put in
+    env.redeem(
+        &wasm,
+        &contract_addr,
+        coin(1_000_000u128, strategy_denom),
+        attacker,
+    )
+    .unwrap();
+
+    let contract_base_denom_balance = env.get_balance(&contract_addr, &config.base_denom);
+    assert_eq!(
+        contract_base_denom_balance,
+        Uint128::from(500_000_125_000u128)
+    );
+}
+
+#[test]
+fn deposit_up_to_strategy_cap() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(30_000_000u128)),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.signer,
+    )
+    .unwrap();
+
+    let res_err = env
+        .deposit(
+            &wasm,
+            &contract_addr,
+            coin(10_000_001u128, "uosmo"),
+            &env.traders[0],
+        )
+        .unwrap_err();
+    assert_err(res_err, VaultenatorError::StrategyCapExceeded {});
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let contract_base_denom_balance = env.get_balance(&contract_addr, "uosmo");
+    assert_eq!(contract_base_denom_balance, Uint128::from(30_000_000u128));
+}
+
+#[test]
+fn deposit_with_recipient() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    let recipient = env.traders[1].address();
+    env.deposit_to(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        Some(recipient.clone()),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    assert_eq!(
+        env.get_balance(&env.traders[0].address(), &strategy_denom),
+        Uint128::zero()
+    );
+    assert_eq!(
+        env.get_balance(&recipient, &strategy_denom),
+        Uint128::from(20_000_000_000_000u128)
+    );
+}
+
+#[test]
+fn redeem_with_recipient() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    let recipient = env.traders[1].address();
+    let recipient_balance_before = env.get_balance(&recipient, "uosmo");
+
+    env.redeem_to(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000_000_000u128, strategy_denom),
+        Some(recipient.clone()),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    assert_eq!(
+        env.get_balance(&recipient, "uosmo"),
+        recipient_balance_before + Uint128::from(20_000_000u128)
+    );
+    assert_eq!(env.get_balance(&contract_addr, "uosmo"), Uint128::zero());
+}
+
+#[test]
+fn deposit_and_redeem_invalid_recipient() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    let res_err = env
+        .deposit_to(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            Some("invalid".to_string()),
+            &env.traders[0],
+        )
+        .unwrap_err();
+    assert!(res_err.to_string().contains("addr_validate"));
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    let res_err = env
+        .redeem_to(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000_000_000u128, strategy_denom),
+            Some("invalid".to_string()),
+            &env.traders[0],
+        )
+        .unwrap_err();
+    assert!(res_err.to_string().contains("addr_validate"));
+}
+
+#[test]
+fn deposit_amount_mismatch() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    let msg = ExecuteMsg::Deposit {
+        amount: Uint128::from(10_000_000u128),
+        recipient: None,
+    };
+    let res_err = wasm
+        .execute(
+            &contract_addr,
+            &msg,
+            &[coin(20_000_000u128, "uosmo")],
+            &env.traders[0],
+        )
+        .unwrap_err();
+
+    assert_err(
+        res_err,
+        ContractError::FundsMismatch {
+            amount: Uint128::from(10_000_000u128),
+            funds: Uint128::from(20_000_000u128),
+        },
+    );
+}
+
+#[test]
+fn redeem_amount_mismatch() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    let msg = ExecuteMsg::Redeem {
+        amount: Uint128::from(20_000_000_000_001u128),
+        recipient: None,
+    };
+    let res_err = wasm
+        .execute(
+            &contract_addr,
+            &msg,
+            &[coin(20_000_000_000_000u128, strategy_denom)],
+            &env.traders[0],
+        )
+        .unwrap_err();
+
+    assert_err(
+        res_err,
+        ContractError::FundsMismatch {
+            amount: Uint128::from(20_000_000_000_001u128),
+            funds: Uint128::from(20_000_000_000_000u128),
+        },
+    );
+}
+
+#[test]
+fn deposit_and_redeem_with_fees() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let fee_recipient = env.traders[5].address();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            deposit_fee: Some(Decimal::percent(1)),
+            withdrawal_fee: Some(Decimal::percent(2)),
+            fee_recipient: Some(fee_recipient.clone()),
+            ..env.instantiate_msg()
+        },
+    );
+    let fee_recipient_balance_before = env.get_balance(&fee_recipient, "uosmo");
+
+    let res = env
+        .deposit(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            &env.traders[0],
+        )
+        .unwrap();
+
+    assert!(contains_event_with_attributes(
+        &res,
+        "fee",
+        vec![
+            ("fee_type", "deposit"),
+            ("recipient", &fee_recipient),
+            ("amount", "200000"),
+        ]
+    ));
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    assert_eq!(
+        env.get_balance(&env.traders[0].address(), &strategy_denom),
+        Uint128::from(19_800_000_000_000u128)
+    );
+    assert_eq!(
+        env.get_balance(&contract_addr, "uosmo"),
+        Uint128::from(19_800_000u128)
+    );
+
+    let res = env
+        .redeem(
+            &wasm,
+            &contract_addr,
+            coin(19_800_000_000_000u128, strategy_denom),
+            &env.traders[0],
+        )
+        .unwrap();
+
+    assert!(contains_event_with_attributes(
+        &res,
+        "fee",
+        vec![("fee_type", "withdrawal"), ("amount", "396000")]
+    ));
+    assert_eq!(env.get_balance(&contract_addr, "uosmo"), Uint128::zero());
+    assert_eq!(
+        env.get_balance(&fee_recipient, "uosmo"),
+        fee_recipient_balance_before + Uint128::from(596_000u128)
+    );
+}
+
+#[test]
+fn deposit_and_redeem_events() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            deposit_fee: Some(Decimal::percent(1)),
+            withdrawal_fee: Some(Decimal::percent(2)),
+            fee_recipient: Some(env.traders[5].address()),
+            ..env.instantiate_msg()
+        },
+    );
+    let depositor = &env.traders[0];
+    let holder = &env.traders[1];
+    let recipient = &env.traders[2];
+
+    let res = env
+        .deposit_to(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            Some(holder.address()),
+            depositor,
+        )
+        .unwrap();
+
+    assert!(contains_event_with_attributes(
+        &res,
+        "deposit",
+        vec![
+            ("sender", &depositor.address()),
+            ("recipient", &holder.address()),
+            ("amount", "20000000"),
+            ("net_amount", "19800000"),
+            ("shares", "19800000000000"),
+            ("share_price", "0.000001"),
+            ("fee", "200000"),
+        ]
+    ));
+
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+
+    let res = env
+        .redeem_to(
+            &wasm,
+            &contract_addr,
+            coin(9_900_000_000_000u128, strategy_denom),
+            Some(recipient.address()),
+            holder,
+        )
+        .unwrap();
+
+    assert!(contains_event_with_attributes(
+        &res,
+        "redeem",
+        vec![
+            ("sender", &holder.address()),
+            ("recipient", &recipient.address()),
+            ("amount", "9900000"),
+            ("net_amount", "9702000"),
+            ("shares", "9900000000000"),
+            ("share_price", "0.000001"),
+            ("fee", "198000"),
+        ]
+    ));
+}
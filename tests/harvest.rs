@@ -0,0 +1,325 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Uint128};
+use cw_controllers::AdminError;
+use example_vault::errors::ContractError;
+use example_vault::harvest::{RewardRoute, SwapHop};
+use example_vault::msg::{InstantiateMsg, StrategyMsg, UpdateConfigMsg};
+use example_vault::roles::Role;
+use helpers::helpers::{assert_err, contains_event, event_attribute};
+use helpers::setup::TestEnv;
+use osmosis_test_tube::{Account, Module, Wasm};
+use std::str::FromStr;
+use vaultenator::errors::ContractError as VaultenatorError;
+
+fn ion_route(pool_id: u64) -> RewardRoute {
+    RewardRoute {
+        denom: "uion".to_string(),
+        hops: vec![SwapHop {
+            pool_id,
+            token_out_denom: "uosmo".to_string(),
+        }],
+    }
+}
+
+#[test]
+fn harvest_compounds_spread_rewards() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_cl_pool_with_spread_factor("0.003");
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy: Some(StrategyMsg::ConcentratedLiquidity {
+                pool_id,
+                lower_tick: -100_000,
+                upper_tick: 100_000,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let keeper = &env.traders[9];
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        keeper.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    let position_id = env
+        .query_state(&wasm, &contract_addr)
+        .unwrap()
+        .position_id
+        .unwrap();
+
+    // Trade through the vault's range in both directions to earn spread
+    // rewards in both tokens
+    env.swap(
+        pool_id,
+        coin(1_000_000_000u128, "uion"),
+        "uosmo",
+        &env.signer,
+    )
+    .unwrap();
+    env.swap(
+        pool_id,
+        coin(1_000_000_000u128, "uosmo"),
+        "uion",
+        &env.signer,
+    )
+    .unwrap();
+
+    let pair_balance = env.get_balance(&contract_addr, "uion");
+    let res = env.harvest(&wasm, &contract_addr, keeper).unwrap();
+
+    // Pair token rewards are held as part of the strategy rather than sold
+    let claimed = event_attribute(&res, "harvest", "claimed").unwrap();
+    assert!(claimed.contains("uosmo"));
+    assert!(env.get_balance(&contract_addr, "uion") > pair_balance);
+    let reinvested =
+        Uint128::from_str(&event_attribute(&res, "harvest", "reinvested").unwrap()).unwrap();
+    assert!(!reinvested.is_zero());
+
+    // Rewards are added back to the position
+    assert!(contains_event(&res, "add_to_position"));
+    let state = env.query_state(&wasm, &contract_addr).unwrap();
+    assert_ne!(state.position_id.unwrap(), position_id);
+}
+
+#[test]
+fn harvest_sells_rewards_sent_to_vault() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            reward_routes: Some(vec![ion_route(pool_id)]),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        env.signer.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    env.send(&contract_addr, coin(1_000_000u128, "uion"), &env.signer)
+        .unwrap();
+
+    let res = env.harvest(&wasm, &contract_addr, &env.signer).unwrap();
+
+    // Nothing is claimed without a strategy but the rewards held are sold
+    assert_eq!(
+        event_attribute(&res, "harvest", "claimed"),
+        Some("none".to_string())
+    );
+    let reinvested =
+        Uint128::from_str(&event_attribute(&res, "harvest", "reinvested").unwrap()).unwrap();
+    assert!(reinvested > Uint128::from(980_000u128));
+    assert_eq!(env.get_balance(&contract_addr, "uion"), Uint128::zero());
+    assert_eq!(env.get_balance(&contract_addr, "uosmo"), reinvested);
+}
+
+#[test]
+fn harvest_swaps_bounded_by_twap() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            reward_routes: Some(vec![ion_route(pool_id)]),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.send(&contract_addr, coin(1_000_000u128, "uion"), &env.signer)
+        .unwrap();
+
+    // Halves the spot price of uion in the pool ahead of the harvest
+    env.swap(pool_id, coin(414_000_000_000, "uion"), "uosmo", &env.signer)
+        .unwrap();
+
+    let err = env.harvest(&wasm, &contract_addr, &env.signer).unwrap_err();
+    assert!(err.to_string().contains("min amount"));
+    assert_eq!(
+        env.get_balance(&contract_addr, "uion"),
+        Uint128::from(1_000_000u128)
+    );
+}
+
+#[test]
+fn harvest_while_paused() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    env.set_pause(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let err = env.harvest(&wasm, &contract_addr, &env.signer).unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+}
+
+#[test]
+fn harvest_not_keeper() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    // The owner can always harvest
+    env.harvest(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let keeper = &env.traders[0];
+    let err = env.harvest(&wasm, &contract_addr, keeper).unwrap_err();
+    assert_err(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        keeper.address(),
+        &env.signer,
+    )
+    .unwrap();
+    env.harvest(&wasm, &contract_addr, keeper).unwrap();
+
+    // Removed keepers can no longer harvest
+    env.revoke_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        keeper.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    let err = env.harvest(&wasm, &contract_addr, keeper).unwrap_err();
+    assert_err(err, ContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn reward_routes_cannot_sell_vault_assets() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(100_000_000u128)),
+            strategy: Some(StrategyMsg::Gamm {
+                pool_id,
+                twap_window: None,
+                max_slippage: None,
+            }),
+            ..env.instantiate_msg()
+        },
+    );
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+
+    // The pair asset, the LP shares and the vault's own token
+    for denom in [
+        "uion".to_string(),
+        format!("gamm/pool/{}", pool_id),
+        strategy_denom,
+    ] {
+        let route = RewardRoute {
+            denom: denom.clone(),
+            ..ion_route(pool_id)
+        };
+        let err = env
+            .update_config(
+                &wasm,
+                &contract_addr,
+                UpdateConfigMsg {
+                    reward_routes: Some(vec![route]),
+                    ..Default::default()
+                },
+                &env.signer,
+            )
+            .unwrap_err();
+        assert_err(err, ContractError::InvalidRewardRoute { denom });
+    }
+}
+
+#[test]
+fn update_config_reward_routes() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let pool_id = env.create_pool();
+    let contract_addr = env.deploy_open_contract(&wasm);
+
+    // Routes must end in the base denom
+    let mut route = ion_route(pool_id);
+    route.hops[0].token_out_denom = "uion".to_string();
+    let err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                reward_routes: Some(vec![route]),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap_err();
+    assert_err(
+        err,
+        ContractError::InvalidRewardRoute {
+            denom: "uion".to_string(),
+        },
+    );
+
+    // Each denom can only have one route
+    let err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                reward_routes: Some(vec![ion_route(pool_id), ion_route(pool_id)]),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap_err();
+    assert_err(
+        err,
+        ContractError::InvalidRewardRoute {
+            denom: "uion".to_string(),
+        },
+    );
+
+    env.update_config(
+        &wasm,
+        &contract_addr,
+        UpdateConfigMsg {
+            reward_routes: Some(vec![ion_route(pool_id)]),
+            ..Default::default()
+        },
+        &env.signer,
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.reward_routes, vec![ion_route(pool_id)]);
+}
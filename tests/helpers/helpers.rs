@@ -81,3 +81,18 @@ pub fn contains_event_with_attributes(
             })
     })
 }
+
+pub fn event_attribute(
+    response: &ExecuteResponse<MsgExecuteContractResponse>,
+    event_type: &str,
+    key: &str,
+) -> Option<String> {
+    let event_type_with_prefix = format!("wasm-{}", event_type);
+    response
+        .events
+        .iter()
+        .filter(|event| event.ty == event_type_with_prefix)
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.clone())
+}
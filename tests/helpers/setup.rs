@@ -1,20 +1,52 @@
 use super::helpers::store_code;
 use cosmwasm_std::{coin, Addr, Coin, Uint128};
+use cw_utils::Duration;
+use cw_vault_standard::extensions::force_unlock::ForceUnlockExecuteMsg;
+use cw_vault_standard::extensions::lockup::{LockupExecuteMsg, LockupQueryMsg, UnlockingPosition};
 use example_vault::config::MyConfig;
+use example_vault::msg::{
+    AprResponse, ExecuteMsg as MyExecuteMsg, ExtensionExecuteMsg as MyExtensionExecuteMsg,
+    ExtensionQueryMsg as MyExtensionQueryMsg, InstantiateMsg, MyVaultExtensionExecuteMsg,
+    MyVaultExtensionQueryMsg, PendingFeesResponse, QueryMsg as MyQueryMsg, UpdateConfigMsg,
+    UserPositionResponse,
+};
+use example_vault::pause::PauseRecord;
+use example_vault::positions::Depositor;
+use example_vault::roles::Role;
+use example_vault::share_price::SharePriceSnapshot;
 use example_vault::state::MyState;
+use example_vault::strategy::DEFAULT_TWAP_WINDOW;
+use example_vault::timelock::PendingConfigChange;
+use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmosisCoin;
 use osmosis_std::types::cosmwasm::wasm::v1::MsgExecuteContractResponse;
+use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::{
+    CreateConcentratedLiquidityPoolsProposal, MsgCreatePosition, Pool, PoolRecord, PoolsRequest,
+};
+use osmosis_std::types::osmosis::gamm::poolmodels::balancer::v1beta1::MsgCreateBalancerPool;
+use osmosis_std::types::osmosis::gamm::v1beta1::{PoolAsset, PoolParams};
+use osmosis_std::types::osmosis::poolmanager::v1beta1::{
+    MsgSwapExactAmountIn, MsgSwapExactAmountInResponse, SwapAmountInRoute,
+};
 use osmosis_test_tube::{
-    osmosis_std::types::cosmos::bank::v1beta1::{QueryBalanceRequest, QueryTotalSupplyRequest},
-    Bank, Module, OsmosisTestApp, RunnerExecuteResult, RunnerResult, SigningAccount, Wasm,
+    osmosis_std::types::cosmos::bank::v1beta1::{
+        MsgSend, MsgSendResponse, QueryBalanceRequest, QueryTotalSupplyRequest,
+    },
+    Account, Bank, ConcentratedLiquidity, Gamm, GovWithAppAccess, Module, OsmosisTestApp,
+    PoolManager, RunnerExecuteResult, RunnerResult, SigningAccount, Wasm,
 };
 use std::str::FromStr;
 use vaultenator::msg::{
-    ExecuteMsg, ExtensionExecuteMsg, ExtensionQueryMsg, InstantiateMsg, QueryMsg,
-    VaultenatorExtensionExecuteMsg, VaultenatorExtensionQueryMsg,
+    ExecuteMsg, ExtensionExecuteMsg, ExtensionQueryMsg, QueryMsg, VaultenatorExtensionExecuteMsg,
+    VaultenatorExtensionQueryMsg,
 };
 use vaultenator::ownership::OwnerProposal;
 
 pub const PROPOSAL_DURATION: u64 = 1000;
+pub const LOCKUP_DURATION: u64 = 86400;
+pub const CL_TICK_SPACING: u64 = 100;
+pub const CL_LOWER_TICK: i64 = -100_000;
+pub const CL_UPPER_TICK: i64 = 100_000;
+pub const CONFIG_TIMELOCK: u64 = 86400;
 
 pub struct TestEnv {
     pub app: OsmosisTestApp,
@@ -26,7 +58,10 @@ impl TestEnv {
         let app = OsmosisTestApp::new();
 
         let signer = app
-            .init_account(&[coin(1_000_000_000_000_000_000, "uosmo")])
+            .init_account(&[
+                coin(1_000_000_000_000_000_000, "uion"),
+                coin(1_000_000_000_000_000_000, "uosmo"),
+            ])
             .unwrap();
 
         let mut traders: Vec<SigningAccount> = Vec::new();
@@ -43,21 +78,172 @@ impl TestEnv {
             traders,
         }
     }
-    pub fn deploy_contract(&self, wasm: &Wasm<OsmosisTestApp>) -> String {
-        let code_id = store_code(&wasm, &self.signer, env!("CARGO_PKG_NAME")).unwrap();
-        wasm.instantiate(
-            code_id,
-            &InstantiateMsg {
-                base_denom: "uosmo".to_string(),
+    pub fn instantiate_msg(&self) -> InstantiateMsg {
+        InstantiateMsg {
+            base_denom: "uosmo".to_string(),
+            strategy_cap: None,
+            lockup_duration: None,
+            deposit_fee: None,
+            withdrawal_fee: None,
+            performance_fee: None,
+            management_fee: None,
+            fee_recipient: None,
+            strategy: None,
+            reward_routes: None,
+            config_timelock: None,
+            max_pause_duration: None,
+            share_price_snapshot_interval: None,
+        }
+    }
+
+    /// Creates a balancer pool with equal uion and uosmo liquidity and lets
+    /// `DEFAULT_TWAP_WINDOW` pass so strategies can price it, returning its
+    /// pool id.
+    pub fn create_pool(&self) -> u64 {
+        let gamm = Gamm::new(&self.app);
+        let pool_id = gamm
+            .create_basic_pool(
+                &[
+                    coin(1_000_000_000_000, "uion"),
+                    coin(1_000_000_000_000, "uosmo"),
+                ],
+                &self.signer,
+            )
+            .unwrap()
+            .data
+            .pool_id;
+
+        self.app.increase_time(DEFAULT_TWAP_WINDOW);
+
+        pool_id
+    }
+
+    /// Creates a balancer pool weighting uion three times as heavily as
+    /// uosmo, returning its pool id.
+    pub fn create_weighted_pool(&self) -> u64 {
+        let gamm = Gamm::new(&self.app);
+        let pool_assets = [("uion", "3000000"), ("uosmo", "1000000")]
+            .into_iter()
+            .map(|(denom, weight)| PoolAsset {
+                token: Some(OsmosisCoin {
+                    denom: denom.to_string(),
+                    amount: "1000000000000".to_string(),
+                }),
+                weight: weight.to_string(),
+            })
+            .collect();
+
+        gamm.create_balancer_pool(
+            MsgCreateBalancerPool {
+                sender: self.signer.address(),
+                pool_params: Some(PoolParams {
+                    swap_fee: "10000000000000000".to_string(),
+                    exit_fee: "0".to_string(),
+                    smooth_weight_change_params: None,
+                }),
+                pool_assets,
+                future_pool_governor: "".to_string(),
             },
-            None,
-            Some("example-vault"),
-            &[],
             &self.signer,
         )
         .unwrap()
         .data
-        .address
+        .pool_id
+    }
+
+    /// Creates a concentrated liquidity pool of uion and uosmo that charges
+    /// no spread, returning its pool id.
+    pub fn create_cl_pool(&self) -> u64 {
+        self.create_cl_pool_with_spread_factor("0")
+    }
+
+    /// Creates a concentrated liquidity pool of uion and uosmo through
+    /// governance, seeds it with a wide position at a price of one and lets
+    /// `DEFAULT_TWAP_WINDOW` pass so strategies can price it, returning its
+    /// pool id.
+    pub fn create_cl_pool_with_spread_factor(&self, spread_factor: &str) -> u64 {
+        let gov = GovWithAppAccess::new(&self.app);
+        gov.propose_and_execute(
+            CreateConcentratedLiquidityPoolsProposal::TYPE_URL.to_string(),
+            CreateConcentratedLiquidityPoolsProposal {
+                title: "Create uion:uosmo pool".to_string(),
+                description: "Create concentrated uion:uosmo pool".to_string(),
+                pool_records: vec![PoolRecord {
+                    denom0: "uion".to_string(),
+                    denom1: "uosmo".to_string(),
+                    tick_spacing: CL_TICK_SPACING,
+                    spread_factor: spread_factor.to_string(),
+                }],
+            },
+            self.signer.address(),
+            &self.signer,
+        )
+        .unwrap();
+
+        let concentrated_liquidity = ConcentratedLiquidity::new(&self.app);
+        let pools = concentrated_liquidity
+            .query_pools(&PoolsRequest { pagination: None })
+            .unwrap();
+        let pool_id = Pool::try_from(pools.pools[0].clone()).unwrap().id;
+
+        concentrated_liquidity
+            .create_position(
+                MsgCreatePosition {
+                    pool_id,
+                    sender: self.signer.address(),
+                    lower_tick: -5_000_000,
+                    upper_tick: 5_000_000,
+                    tokens_provided: vec![
+                        OsmosisCoin {
+                            denom: "uion".to_string(),
+                            amount: "1000000000000".to_string(),
+                        },
+                        OsmosisCoin {
+                            denom: "uosmo".to_string(),
+                            amount: "1000000000000".to_string(),
+                        },
+                    ],
+                    token_min_amount0: "0".to_string(),
+                    token_min_amount1: "0".to_string(),
+                },
+                &self.signer,
+            )
+            .unwrap();
+
+        self.app.increase_time(DEFAULT_TWAP_WINDOW);
+
+        pool_id
+    }
+
+    pub fn deploy_contract(&self, wasm: &Wasm<OsmosisTestApp>) -> String {
+        self.deploy_contract_with_msg(wasm, &self.instantiate_msg())
+    }
+
+    pub fn deploy_contract_with_msg(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        msg: &InstantiateMsg,
+    ) -> String {
+        let code_id = store_code(&wasm, &self.signer, env!("CARGO_PKG_NAME")).unwrap();
+        wasm.instantiate(code_id, msg, None, Some("example-vault"), &[], &self.signer)
+            .unwrap()
+            .data
+            .address
+    }
+
+    /// Deploys the contract and opens it for deposits.
+    pub fn deploy_open_contract(&self, wasm: &Wasm<OsmosisTestApp>) -> String {
+        self.deploy_open_contract_with_msg(wasm, &self.instantiate_msg())
+    }
+
+    pub fn deploy_open_contract_with_msg(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        msg: &InstantiateMsg,
+    ) -> String {
+        let contract_addr = self.deploy_contract_with_msg(wasm, msg);
+        self.set_open(wasm, &contract_addr, &self.signer).unwrap();
+        contract_addr
     }
 
     pub fn propose_new_owner(
@@ -84,10 +270,21 @@ impl TestEnv {
         contract_addr: &str,
         amount: Coin,
         signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        self.deposit_to(wasm, contract_addr, amount, None, signer)
+    }
+
+    pub fn deposit_to(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Coin,
+        recipient: Option<String>,
+        signer: &SigningAccount,
     ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
         let msg = ExecuteMsg::Deposit {
-            amount: Uint128::one(),
-            recipient: None,
+            amount: amount.amount,
+            recipient,
         };
         wasm.execute(contract_addr, &msg, &[amount], signer)
     }
@@ -98,14 +295,124 @@ impl TestEnv {
         contract_addr: &str,
         amount: Coin,
         signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        self.redeem_to(wasm, contract_addr, amount, None, signer)
+    }
+
+    pub fn redeem_to(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Coin,
+        recipient: Option<String>,
+        signer: &SigningAccount,
     ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
         let msg = ExecuteMsg::Redeem {
-            amount: Uint128::one(),
-            recipient: None,
+            amount: amount.amount,
+            recipient,
         };
         wasm.execute(contract_addr, &msg, &[amount], signer)
     }
 
+    pub fn unlock(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Coin,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg =
+            MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::Lockup(LockupExecuteMsg::Unlock {
+                amount: amount.amount,
+            }));
+        wasm.execute(contract_addr, &msg, &[amount], signer)
+    }
+
+    pub fn emergency_unlock(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Coin,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::Lockup(
+            LockupExecuteMsg::EmergencyUnlock {
+                amount: amount.amount,
+            },
+        ));
+        wasm.execute(contract_addr, &msg, &[amount], signer)
+    }
+
+    pub fn withdraw_unlocked(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        lockup_id: u64,
+        recipient: Option<String>,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::Lockup(
+            LockupExecuteMsg::WithdrawUnlocked {
+                recipient,
+                lockup_id,
+            },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn force_redeem(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Coin,
+        recipient: Option<String>,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::ForceUnlock(
+            ForceUnlockExecuteMsg::ForceRedeem {
+                recipient,
+                amount: amount.amount,
+            },
+        ));
+        wasm.execute(contract_addr, &msg, &[amount], signer)
+    }
+
+    pub fn force_withdraw_unlocking(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        lockup_id: u64,
+        amount: Option<Uint128>,
+        recipient: Option<String>,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::ForceUnlock(
+            ForceUnlockExecuteMsg::ForceWithdrawUnlocking {
+                lockup_id,
+                amount,
+                recipient,
+            },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn update_force_withdraw_whitelist(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        add_addresses: Vec<String>,
+        remove_addresses: Vec<String>,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::ForceUnlock(
+            ForceUnlockExecuteMsg::UpdateForceWithdrawWhitelist {
+                add_addresses,
+                remove_addresses,
+            },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
     pub fn claim_ownership(
         &self,
         wasm: &Wasm<OsmosisTestApp>,
@@ -130,6 +437,179 @@ impl TestEnv {
         wasm.execute(contract_addr, &reject_owner_msg, &[], signer)
     }
 
+    pub fn update_config(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        update_config_msg: UpdateConfigMsg,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::UpdateConfig(update_config_msg),
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn propose_config_change(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        update_config_msg: UpdateConfigMsg,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::ProposeConfigChange(update_config_msg),
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn execute_config_change(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        id: u64,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::ExecuteConfigChange { id },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn cancel_config_change(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        id: u64,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::CancelConfigChange { id },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn accrue_fees(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::AccrueFees {},
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn rebalance(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        lower_tick: i64,
+        upper_tick: i64,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::Rebalance {
+                lower_tick,
+                upper_tick,
+            },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn harvest(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::Harvest {},
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn pause_with_reason(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        reason: Option<String>,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::Pause { reason },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn unpause_with_reason(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        reason: Option<String>,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::Unpause { reason },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn grant_role(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        role: Role,
+        address: String,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::GrantRole { role, address },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    pub fn revoke_role(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        role: Role,
+        address: String,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgExecuteContractResponse> {
+        let msg = MyExecuteMsg::VaultExtension(MyExtensionExecuteMsg::MyVault(
+            MyVaultExtensionExecuteMsg::RevokeRole { role, address },
+        ));
+        wasm.execute(contract_addr, &msg, &[], signer)
+    }
+
+    /// Swaps `amount` through a single pool.
+    pub fn swap(
+        &self,
+        pool_id: u64,
+        amount: Coin,
+        token_out_denom: &str,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgSwapExactAmountInResponse> {
+        let pool_manager = PoolManager::new(&self.app);
+        pool_manager.swap_exact_amount_in(
+            MsgSwapExactAmountIn {
+                sender: signer.address(),
+                routes: vec![SwapAmountInRoute {
+                    pool_id,
+                    token_out_denom: token_out_denom.to_string(),
+                }],
+                token_in: Some(OsmosisCoin {
+                    denom: amount.denom,
+                    amount: amount.amount.to_string(),
+                }),
+                token_out_min_amount: "1".to_string(),
+            },
+            signer,
+        )
+    }
+
     pub fn query_owner(
         &self,
         wasm: &Wasm<OsmosisTestApp>,
@@ -178,6 +658,241 @@ impl TestEnv {
         wasm.query(contract_addr, &query_msg)
     }
 
+    pub fn query_preview_deposit(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Uint128,
+    ) -> RunnerResult<Uint128> {
+        wasm.query(contract_addr, &MyQueryMsg::PreviewDeposit { amount })
+    }
+
+    pub fn query_preview_redeem(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Uint128,
+    ) -> RunnerResult<Uint128> {
+        wasm.query(contract_addr, &MyQueryMsg::PreviewRedeem { amount })
+    }
+
+    pub fn query_total_assets(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+    ) -> RunnerResult<Uint128> {
+        wasm.query(contract_addr, &MyQueryMsg::TotalAssets {})
+    }
+
+    pub fn query_total_vault_token_supply(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+    ) -> RunnerResult<Uint128> {
+        wasm.query(contract_addr, &MyQueryMsg::TotalVaultTokenSupply {})
+    }
+
+    pub fn query_convert_to_shares(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Uint128,
+    ) -> RunnerResult<Uint128> {
+        wasm.query(contract_addr, &MyQueryMsg::ConvertToShares { amount })
+    }
+
+    pub fn query_convert_to_assets(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        amount: Uint128,
+    ) -> RunnerResult<Uint128> {
+        wasm.query(contract_addr, &MyQueryMsg::ConvertToAssets { amount })
+    }
+
+    pub fn query_remaining_capacity(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+    ) -> RunnerResult<Option<Uint128>> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::RemainingCapacity {},
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_pending_fees(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+    ) -> RunnerResult<PendingFeesResponse> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::PendingFees {},
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_has_role(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        role: Role,
+        address: String,
+    ) -> RunnerResult<bool> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::HasRole { role, address },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_role_members(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> RunnerResult<Vec<Addr>> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::RoleMembers {
+                role,
+                start_after,
+                limit,
+            },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_pause_history(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> RunnerResult<Vec<PauseRecord>> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::PauseHistory { start_after, limit },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_pending_config_changes(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> RunnerResult<Vec<PendingConfigChange>> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::PendingConfigChanges { start_after, limit },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_user_position(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        address: String,
+    ) -> RunnerResult<UserPositionResponse> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::UserPosition { address },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_depositors(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> RunnerResult<Vec<Depositor>> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::Depositors { start_after, limit },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_share_price_history(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> RunnerResult<Vec<SharePriceSnapshot>> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::SharePriceHistory { start_after, limit },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_apr(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        window_seconds: u64,
+    ) -> RunnerResult<Option<AprResponse>> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::MyVault(
+            MyVaultExtensionQueryMsg::Apr { window_seconds },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_unlocking_positions(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> RunnerResult<Vec<UnlockingPosition>> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::Lockup(
+            LockupQueryMsg::UnlockingPositions {
+                owner,
+                start_after,
+                limit,
+            },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_unlocking_position(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+        lockup_id: u64,
+    ) -> RunnerResult<UnlockingPosition> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::Lockup(
+            LockupQueryMsg::UnlockingPosition { lockup_id },
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
+    pub fn query_lockup_duration(
+        &self,
+        wasm: &Wasm<OsmosisTestApp>,
+        contract_addr: &str,
+    ) -> RunnerResult<Duration> {
+        let query_msg = MyQueryMsg::VaultExtension(MyExtensionQueryMsg::Lockup(
+            LockupQueryMsg::LockupDuration {},
+        ));
+
+        wasm.query(contract_addr, &query_msg)
+    }
+
     pub fn set_open(
         &self,
         wasm: &Wasm<OsmosisTestApp>,
@@ -214,6 +929,27 @@ impl TestEnv {
         wasm.execute(contract_addr, &set_unpause_msg, &[], signer)
     }
 
+    pub fn send(
+        &self,
+        to_address: &str,
+        amount: Coin,
+        signer: &SigningAccount,
+    ) -> RunnerExecuteResult<MsgSendResponse> {
+        let bank = Bank::new(&self.app);
+
+        bank.send(
+            MsgSend {
+                from_address: signer.address(),
+                to_address: to_address.to_string(),
+                amount: vec![OsmosisCoin {
+                    denom: amount.denom,
+                    amount: amount.amount.to_string(),
+                }],
+            },
+            signer,
+        )
+    }
+
     pub fn get_balance(&self, address: &str, denom: &str) -> Uint128 {
         let bank = Bank::new(&self.app);
 
@@ -0,0 +1,146 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::Uint128;
+use cw_controllers::AdminError;
+use example_vault::errors::ContractError as MyContractError;
+use example_vault::msg::UpdateConfigMsg;
+use example_vault::roles::Role;
+use helpers::helpers::{assert_err, contains_event};
+use helpers::setup::{TestEnv, PROPOSAL_DURATION};
+use osmosis_test_tube::{Account, Module, Wasm};
+use vaultenator::errors::ContractError;
+
+#[test]
+fn grant_keeper_not_owner() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let res_err = env
+        .grant_role(
+            &wasm,
+            &contract_addr,
+            Role::Keeper,
+            env.traders[0].address(),
+            &env.traders[0],
+        )
+        .unwrap_err();
+
+    assert_err(res_err, MyContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn keeper_can_pause() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let keeper = &env.traders[9];
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        keeper.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    env.set_open(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let res = env.set_pause(&wasm, &contract_addr, keeper).unwrap();
+    let state = env.query_state(&wasm, &contract_addr).unwrap();
+    assert!(state.is_paused);
+    assert!(contains_event(&res, "paused"));
+}
+
+#[test]
+fn keeper_cannot_open_or_unpause() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let keeper = &env.traders[9];
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        keeper.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    let res_err = env.set_open(&wasm, &contract_addr, keeper).unwrap_err();
+    assert_err(res_err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    env.set_open(&wasm, &contract_addr, &env.signer).unwrap();
+    env.set_pause(&wasm, &contract_addr, keeper).unwrap();
+
+    let res_err = env.set_unpause(&wasm, &contract_addr, keeper).unwrap_err();
+    assert_err(res_err, ContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn keeper_cannot_update_config() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let keeper = &env.traders[9];
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        keeper.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    let res_err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                strategy_cap: Some(Some(Uint128::from(1_000u128))),
+                ..Default::default()
+            },
+            keeper,
+        )
+        .unwrap_err();
+    assert_err(res_err, MyContractError::Admin(AdminError::NotAdmin {}));
+
+    // Nor grant other keepers
+    let res_err = env
+        .grant_role(
+            &wasm,
+            &contract_addr,
+            Role::Keeper,
+            env.traders[0].address(),
+            keeper,
+        )
+        .unwrap_err();
+    assert_err(res_err, MyContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn keeper_cannot_propose_owner() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let keeper = &env.traders[9];
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        keeper.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    let res_err = env
+        .propose_new_owner(
+            &wasm,
+            &contract_addr,
+            keeper.address(),
+            PROPOSAL_DURATION,
+            keeper,
+        )
+        .unwrap_err();
+    assert_err(res_err, ContractError::Admin(AdminError::NotAdmin {}));
+}
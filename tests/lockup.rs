@@ -0,0 +1,338 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+use cw_utils::{Duration, Expiration};
+use example_vault::errors::ContractError as MyContractError;
+use example_vault::fees::SECONDS_PER_YEAR;
+use example_vault::msg::InstantiateMsg;
+use helpers::helpers::{assert_err, contains_event, contains_event_with_attributes};
+use helpers::setup::{TestEnv, LOCKUP_DURATION};
+use osmosis_test_tube::{Account, Module, Wasm};
+use vaultenator::errors::ContractError;
+
+#[test]
+fn redeem_with_lockup() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.signer,
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    let res_err = env
+        .redeem(
+            &wasm,
+            &contract_addr,
+            coin(20_000_000_000_000u128, strategy_denom),
+            &env.signer,
+        )
+        .unwrap_err();
+
+    assert_err(res_err, MyContractError::LockupEnabled {});
+}
+
+#[test]
+fn unlock_and_withdraw_unlocked() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    let res = env
+        .unlock(
+            &wasm,
+            &contract_addr,
+            coin(10_000_000_000_000u128, strategy_denom.clone()),
+            &env.traders[0],
+        )
+        .unwrap();
+
+    let release_at = env.app.get_block_timestamp().plus_seconds(LOCKUP_DURATION);
+
+    assert!(contains_event_with_attributes(
+        &res,
+        "unlocking_position_created",
+        vec![("lockup_id", "1"), ("base_token_amount", "10000000")]
+    ));
+
+    let position = env
+        .query_unlocking_position(&wasm, &contract_addr, 1)
+        .unwrap();
+    assert_eq!(position.owner, Addr::unchecked(env.traders[0].address()));
+    assert_eq!(position.base_token_amount, Uint128::from(10_000_000u128));
+    assert_eq!(position.release_at, Expiration::AtTime(release_at));
+
+    // Unlocked vault tokens are burned and their base tokens no longer count
+    // towards the share price
+    assert_eq!(
+        env.get_total_supply(&strategy_denom),
+        Uint128::from(10_000_000_000_000u128)
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000u128, "uosmo"),
+        &env.traders[1],
+    )
+    .unwrap();
+    assert_eq!(
+        env.get_balance(&env.traders[1].address(), &strategy_denom),
+        Uint128::from(10_000_000_000_000u128)
+    );
+
+    let res_err = env
+        .withdraw_unlocked(&wasm, &contract_addr, 1, None, &env.traders[0])
+        .unwrap_err();
+    assert_err(
+        res_err,
+        MyContractError::UnlockingPositionLocked { lockup_id: 1 },
+    );
+
+    env.app.increase_time(LOCKUP_DURATION + 1);
+
+    let recipient = env.traders[2].address();
+    let res = env
+        .withdraw_unlocked(
+            &wasm,
+            &contract_addr,
+            1,
+            Some(recipient.clone()),
+            &env.traders[0],
+        )
+        .unwrap();
+
+    assert!(contains_event_with_attributes(
+        &res,
+        "withdraw_unlocked",
+        vec![
+            ("lockup_id", "1"),
+            ("recipient", &recipient),
+            ("base_token_amount", "10000000"),
+        ]
+    ));
+    assert_eq!(
+        env.get_balance(&contract_addr, "uosmo"),
+        Uint128::from(20_000_000u128)
+    );
+
+    let res_err = env
+        .query_unlocking_position(&wasm, &contract_addr, 1)
+        .unwrap_err();
+    assert!(res_err.to_string().contains("not found"));
+}
+
+#[test]
+fn withdraw_unlocked_not_owner() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    env.unlock(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000_000_000u128, strategy_denom),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    env.app.increase_time(LOCKUP_DURATION + 1);
+
+    let res_err = env
+        .withdraw_unlocked(&wasm, &contract_addr, 1, None, &env.traders[1])
+        .unwrap_err();
+    assert_err(res_err, ContractError::Unauthorized {});
+
+    let res_err = env
+        .withdraw_unlocked(&wasm, &contract_addr, 2, None, &env.traders[0])
+        .unwrap_err();
+    assert_err(
+        res_err,
+        MyContractError::UnlockingPositionNotFound { lockup_id: 2 },
+    );
+}
+
+#[test]
+fn emergency_unlock_pays_accrued_fees() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let msg = InstantiateMsg {
+        lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+        management_fee: Some(Decimal::percent(2)),
+        fee_recipient: Some(env.traders[5].address()),
+        ..env.instantiate_msg()
+    };
+    let unlock_addr = env.deploy_open_contract_with_msg(&wasm, &msg);
+    let emergency_addr = env.deploy_open_contract_with_msg(&wasm, &msg);
+
+    for contract_addr in [&unlock_addr, &emergency_addr] {
+        env.deposit(
+            &wasm,
+            contract_addr,
+            coin(20_000_000u128, "uosmo"),
+            &env.traders[0],
+        )
+        .unwrap();
+    }
+
+    let unlock_denom = env
+        .query_config(&wasm, &unlock_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+    let emergency_denom = env
+        .query_config(&wasm, &emergency_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+
+    env.app.increase_time(SECONDS_PER_YEAR);
+
+    env.unlock(
+        &wasm,
+        &unlock_addr,
+        coin(20_000_000_000_000u128, unlock_denom),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    // No fee shares are minted but the vault tokens are priced as if they were
+    let res = env
+        .emergency_unlock(
+            &wasm,
+            &emergency_addr,
+            coin(20_000_000_000_000u128, emergency_denom.clone()),
+            &env.traders[0],
+        )
+        .unwrap();
+    assert!(!contains_event(&res, "fee"));
+    assert_eq!(env.get_total_supply(&emergency_denom), Uint128::zero());
+
+    let unlocked = env
+        .query_unlocking_position(&wasm, &unlock_addr, 1)
+        .unwrap()
+        .base_token_amount;
+    let position = env
+        .query_unlocking_position(&wasm, &emergency_addr, 1)
+        .unwrap();
+    assert_eq!(position.owner, Addr::unchecked(env.traders[0].address()));
+    assert!(unlocked < Uint128::from(19_700_000u128));
+    assert!(position.base_token_amount.abs_diff(unlocked) <= Uint128::from(10u128));
+}
+
+#[test]
+fn query_unlocking_positions() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+            ..env.instantiate_msg()
+        },
+    );
+
+    for trader in &env.traders[0..2] {
+        env.deposit(&wasm, &contract_addr, coin(20_000_000u128, "uosmo"), trader)
+            .unwrap();
+    }
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    let strategy_denom = config.strategy_denom.unwrap();
+
+    // Positions 1, 3 and 4 belong to the first trader
+    for trader in [
+        &env.traders[0],
+        &env.traders[1],
+        &env.traders[0],
+        &env.traders[0],
+    ] {
+        env.unlock(
+            &wasm,
+            &contract_addr,
+            coin(5_000_000_000_000u128, strategy_denom.clone()),
+            trader,
+        )
+        .unwrap();
+    }
+
+    let owner = env.traders[0].address();
+    let positions = env
+        .query_unlocking_positions(&wasm, &contract_addr, owner.clone(), None, None)
+        .unwrap();
+    let ids: Vec<u64> = positions.iter().map(|position| position.id).collect();
+    assert_eq!(ids, vec![1, 3, 4]);
+
+    let positions = env
+        .query_unlocking_positions(&wasm, &contract_addr, owner, Some(1), Some(1))
+        .unwrap();
+    let ids: Vec<u64> = positions.iter().map(|position| position.id).collect();
+    assert_eq!(ids, vec![3]);
+}
+
+#[test]
+fn query_lockup_duration() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            lockup_duration: Some(Duration::Time(LOCKUP_DURATION)),
+            ..env.instantiate_msg()
+        },
+    );
+    let lockup_duration = env.query_lockup_duration(&wasm, &contract_addr).unwrap();
+    assert_eq!(lockup_duration, Duration::Time(LOCKUP_DURATION));
+
+    let contract_addr = env.deploy_open_contract(&wasm);
+    let lockup_duration = env.query_lockup_duration(&wasm, &contract_addr).unwrap();
+    assert_eq!(lockup_duration, Duration::Time(0));
+}
@@ -0,0 +1,249 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::coin;
+use example_vault::errors::ContractError;
+use example_vault::msg::InstantiateMsg;
+use example_vault::pause::MAX_PAUSE_REASON_LENGTH;
+use example_vault::roles::Role;
+use helpers::helpers::{assert_err, contains_event_with_attributes};
+use helpers::setup::TestEnv;
+use osmosis_test_tube::{Account, Module, Wasm};
+use vaultenator::errors::ContractError as VaultenatorError;
+
+const MAX_PAUSE_DURATION: u64 = 3600;
+
+#[test]
+fn pause_history() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let guardian = &env.traders[0];
+
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Guardian,
+        guardian.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    // Opening unpauses the vault
+    env.set_open(&wasm, &contract_addr, &env.signer).unwrap();
+    let opened_at = env.app.get_block_timestamp();
+
+    let res = env
+        .pause_with_reason(
+            &wasm,
+            &contract_addr,
+            Some("oracle outage".to_string()),
+            guardian,
+        )
+        .unwrap();
+    let paused_at = env.app.get_block_timestamp();
+    assert!(contains_event_with_attributes(
+        &res,
+        "paused",
+        vec![
+            ("pause_record_id", "2"),
+            ("actor", &guardian.address()),
+            ("reason", "oracle outage"),
+        ],
+    ));
+
+    let state = env.query_state(&wasm, &contract_addr).unwrap();
+    assert!(state.is_paused);
+    assert_eq!(state.last_pause, paused_at);
+    assert_eq!(state.last_unpause, Some(opened_at));
+
+    env.unpause_with_reason(
+        &wasm,
+        &contract_addr,
+        Some("oracle restored".to_string()),
+        &env.signer,
+    )
+    .unwrap();
+    let unpaused_at = env.app.get_block_timestamp();
+
+    let state = env.query_state(&wasm, &contract_addr).unwrap();
+    assert!(!state.is_paused);
+    assert_eq!(state.last_unpause, Some(unpaused_at));
+
+    let history = env
+        .query_pause_history(&wasm, &contract_addr, None, None)
+        .unwrap();
+    let records: Vec<_> = history
+        .iter()
+        .map(|record| {
+            (
+                record.id,
+                record.paused,
+                record.actor.to_string(),
+                record.reason.clone(),
+                record.timestamp,
+            )
+        })
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            (1, false, env.signer.address(), None, opened_at),
+            (
+                2,
+                true,
+                guardian.address(),
+                Some("oracle outage".to_string()),
+                paused_at
+            ),
+            (
+                3,
+                false,
+                env.signer.address(),
+                Some("oracle restored".to_string()),
+                unpaused_at
+            ),
+        ]
+    );
+
+    let history = env
+        .query_pause_history(&wasm, &contract_addr, Some(1), Some(1))
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].id, 2);
+}
+
+#[test]
+fn pause_reason_too_long() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    env.set_open(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let err = env
+        .pause_with_reason(
+            &wasm,
+            &contract_addr,
+            Some("a".repeat(MAX_PAUSE_REASON_LENGTH + 1)),
+            &env.signer,
+        )
+        .unwrap_err();
+    assert_err(
+        err,
+        ContractError::PauseReasonTooLong {
+            max: MAX_PAUSE_REASON_LENGTH,
+        },
+    );
+}
+
+#[test]
+fn closed_or_paused_vault_rejects_deposits_and_redeems() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let deposit = coin(1_000_000u128, "uosmo");
+
+    let err = env
+        .deposit(&wasm, &contract_addr, deposit.clone(), &env.traders[0])
+        .unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+
+    env.set_open(&wasm, &contract_addr, &env.signer).unwrap();
+    env.deposit(&wasm, &contract_addr, deposit.clone(), &env.traders[0])
+        .unwrap();
+    env.set_pause(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let err = env
+        .deposit(&wasm, &contract_addr, deposit, &env.traders[0])
+        .unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+    let err = env
+        .redeem(
+            &wasm,
+            &contract_addr,
+            coin(1_000_000_000_000u128, strategy_denom),
+            &env.traders[0],
+        )
+        .unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+}
+
+#[test]
+fn pause_lapses_after_max_pause_duration() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            max_pause_duration: Some(MAX_PAUSE_DURATION),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(1_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+
+    env.set_pause(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let err = env
+        .redeem(
+            &wasm,
+            &contract_addr,
+            coin(500_000_000_000u128, &strategy_denom),
+            &env.traders[0],
+        )
+        .unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+
+    env.app.increase_time(MAX_PAUSE_DURATION);
+
+    // Users can redeem without the vault being unpaused but not deposit
+    env.redeem(
+        &wasm,
+        &contract_addr,
+        coin(250_000_000_000u128, &strategy_denom),
+        &env.traders[0],
+    )
+    .unwrap();
+    assert!(env.query_state(&wasm, &contract_addr).unwrap().is_paused);
+
+    let err = env
+        .deposit(
+            &wasm,
+            &contract_addr,
+            coin(1_000_000u128, "uosmo"),
+            &env.traders[1],
+        )
+        .unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+
+    // A lapsed pause cannot be renewed to keep redeems blocked
+    let err = env
+        .set_pause(&wasm, &contract_addr, &env.signer)
+        .unwrap_err();
+    assert_err(err, VaultenatorError::Paused {});
+
+    env.redeem(
+        &wasm,
+        &contract_addr,
+        coin(250_000_000_000u128, strategy_denom),
+        &env.traders[0],
+    )
+    .unwrap();
+}
@@ -0,0 +1,189 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Decimal, Int128, Uint128};
+use example_vault::msg::InstantiateMsg;
+use helpers::setup::TestEnv;
+use osmosis_test_tube::{Account, Module, Wasm};
+
+#[test]
+fn user_position_tracks_deposits_and_redeems() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            deposit_fee: Some(Decimal::percent(1)),
+            withdrawal_fee: Some(Decimal::percent(1)),
+            fee_recipient: Some(env.traders[9].address()),
+            ..env.instantiate_msg()
+        },
+    );
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+    let depositor = &env.traders[0];
+
+    let position = env
+        .query_user_position(&wasm, &contract_addr, depositor.address())
+        .unwrap();
+    assert_eq!(position.shares, Uint128::zero());
+    assert_eq!(position.deposited, Uint128::zero());
+    assert_eq!(position.pnl, Int128::zero());
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000u128, "uosmo"),
+        depositor,
+    )
+    .unwrap();
+
+    // Fees on the way in and out are a loss until the share price rises
+    let position = env
+        .query_user_position(&wasm, &contract_addr, depositor.address())
+        .unwrap();
+    let shares = env.get_balance(&depositor.address(), &strategy_denom);
+    assert_eq!(position.shares, shares);
+    assert_eq!(position.deposited, Uint128::from(10_000_000u128));
+    assert_eq!(position.withdrawn, Uint128::zero());
+    assert_eq!(
+        position.value,
+        env.query_preview_redeem(&wasm, &contract_addr, shares)
+            .unwrap()
+    );
+    assert!(position.pnl.is_negative());
+
+    env.send(&contract_addr, coin(1_000_000u128, "uosmo"), &env.signer)
+        .unwrap();
+
+    let position = env
+        .query_user_position(&wasm, &contract_addr, depositor.address())
+        .unwrap();
+    assert!(position.pnl > Int128::zero());
+
+    // Redeeming realises part of the gain without changing the profit, paid
+    // to an account that does not sign so gas does not skew the balance
+    let recipient = env.traders[1].address();
+    let balance = env.get_balance(&recipient, "uosmo");
+    env.redeem_to(
+        &wasm,
+        &contract_addr,
+        coin(shares.u128() / 2, &strategy_denom),
+        Some(recipient.clone()),
+        depositor,
+    )
+    .unwrap();
+    let received = env.get_balance(&recipient, "uosmo") - balance;
+
+    let redeemed = env
+        .query_user_position(&wasm, &contract_addr, depositor.address())
+        .unwrap();
+    assert_eq!(redeemed.withdrawn, received);
+    assert_eq!(redeemed.shares, shares - Uint128::from(shares.u128() / 2));
+    assert!(redeemed.pnl.abs_diff(position.pnl) <= Uint128::from(2u128));
+}
+
+#[test]
+fn deposits_count_towards_recipient() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+    let depositor = &env.traders[0];
+    let recipient = &env.traders[1];
+
+    env.deposit_to(
+        &wasm,
+        &contract_addr,
+        coin(1_000_000u128, "uosmo"),
+        Some(recipient.address()),
+        depositor,
+    )
+    .unwrap();
+
+    let position = env
+        .query_user_position(&wasm, &contract_addr, recipient.address())
+        .unwrap();
+    assert_eq!(position.deposited, Uint128::from(1_000_000u128));
+
+    let position = env
+        .query_user_position(&wasm, &contract_addr, depositor.address())
+        .unwrap();
+    assert_eq!(position.deposited, Uint128::zero());
+    assert_eq!(position.shares, Uint128::zero());
+}
+
+#[test]
+fn depositors_lists_share_holders() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+
+    for trader in &env.traders[0..3] {
+        env.deposit(&wasm, &contract_addr, coin(1_000_000u128, "uosmo"), trader)
+            .unwrap();
+    }
+
+    let mut expected: Vec<_> = env.traders[0..3]
+        .iter()
+        .map(|trader| {
+            (
+                trader.address(),
+                env.get_balance(&trader.address(), &strategy_denom),
+            )
+        })
+        .collect();
+    expected.sort();
+
+    let depositors: Vec<_> = env
+        .query_depositors(&wasm, &contract_addr, None, None)
+        .unwrap()
+        .into_iter()
+        .map(|depositor| (depositor.address.to_string(), depositor.shares))
+        .collect();
+    assert_eq!(depositors, expected);
+
+    let page = env
+        .query_depositors(&wasm, &contract_addr, Some(expected[0].0.clone()), Some(1))
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].address.to_string(), expected[1].0);
+
+    // Redeeming part of a position keeps the depositor, redeeming all of it
+    // removes them
+    let trader = &env.traders[0];
+    let shares = env.get_balance(&trader.address(), &strategy_denom);
+    env.redeem(
+        &wasm,
+        &contract_addr,
+        coin(shares.u128() / 2, &strategy_denom),
+        trader,
+    )
+    .unwrap();
+    let depositors = env
+        .query_depositors(&wasm, &contract_addr, None, None)
+        .unwrap();
+    assert_eq!(depositors.len(), 3);
+
+    let shares = env.get_balance(&trader.address(), &strategy_denom);
+    env.redeem(
+        &wasm,
+        &contract_addr,
+        coin(shares.u128(), &strategy_denom),
+        trader,
+    )
+    .unwrap();
+    let depositors = env
+        .query_depositors(&wasm, &contract_addr, None, None)
+        .unwrap();
+    assert_eq!(depositors.len(), 2);
+    assert!(depositors
+        .iter()
+        .all(|depositor| depositor.address != trader.address()));
+}
@@ -0,0 +1,177 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Decimal, Uint128};
+use example_vault::msg::InstantiateMsg;
+use example_vault::pricing::{VIRTUAL_ASSETS, VIRTUAL_SHARES};
+use helpers::setup::TestEnv;
+use osmosis_test_tube::{Account, Module, Wasm};
+
+const AMOUNTS: [u128; 5] = [1_000, 12_345, 999_999, 7_654_321, 20_000_000];
+
+#[test]
+fn preview_deposit_matches_deposit() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    // No management fee as it accrues between the query and the next block
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            deposit_fee: Some(Decimal::percent(1)),
+            withdrawal_fee: Some(Decimal::permille(5)),
+            performance_fee: Some(Decimal::percent(20)),
+            fee_recipient: Some(env.traders[9].address()),
+            ..env.instantiate_msg()
+        },
+    );
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+
+    for (i, amount) in AMOUNTS.into_iter().enumerate() {
+        let amount = Uint128::from(amount);
+        let depositor = &env.traders[i];
+
+        let preview = env
+            .query_preview_deposit(&wasm, &contract_addr, amount)
+            .unwrap();
+
+        let balance = env.get_balance(&depositor.address(), &strategy_denom);
+        env.deposit(
+            &wasm,
+            &contract_addr,
+            coin(amount.u128(), "uosmo"),
+            depositor,
+        )
+        .unwrap();
+        let minted = env.get_balance(&depositor.address(), &strategy_denom) - balance;
+        assert_eq!(minted, preview);
+
+        // Donations raise the share price so later deposits round and pay a
+        // performance fee
+        env.send(&contract_addr, coin(333_333u128, "uosmo"), &env.signer)
+            .unwrap();
+    }
+}
+
+#[test]
+fn preview_redeem_matches_redeem() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            deposit_fee: Some(Decimal::percent(1)),
+            withdrawal_fee: Some(Decimal::permille(5)),
+            performance_fee: Some(Decimal::percent(20)),
+            fee_recipient: Some(env.traders[9].address()),
+            ..env.instantiate_msg()
+        },
+    );
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+    let redeemer = &env.traders[0];
+    let recipient = env.traders[1].address();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(50_000_000u128, "uosmo"),
+        redeemer,
+    )
+    .unwrap();
+
+    for amount in AMOUNTS {
+        env.send(&contract_addr, coin(333_333u128, "uosmo"), &env.signer)
+            .unwrap();
+
+        // Shares carry six more decimals than the base token
+        let amount = Uint128::from(amount * 1_000_000);
+        let preview = env
+            .query_preview_redeem(&wasm, &contract_addr, amount)
+            .unwrap();
+
+        // Paid to an account that does not sign so gas does not skew the
+        // balance
+        let balance = env.get_balance(&recipient, "uosmo");
+        env.redeem_to(
+            &wasm,
+            &contract_addr,
+            coin(amount.u128(), &strategy_denom),
+            Some(recipient.clone()),
+            redeemer,
+        )
+        .unwrap();
+        let received = env.get_balance(&recipient, "uosmo") - balance;
+        assert_eq!(received, preview);
+    }
+}
+
+#[test]
+fn total_assets_and_conversions() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            deposit_fee: Some(Decimal::percent(1)),
+            withdrawal_fee: Some(Decimal::permille(5)),
+            performance_fee: Some(Decimal::percent(20)),
+            fee_recipient: Some(env.traders[9].address()),
+            ..env.instantiate_msg()
+        },
+    );
+    let strategy_denom = env
+        .query_config(&wasm, &contract_addr)
+        .unwrap()
+        .strategy_denom
+        .unwrap();
+
+    assert_eq!(
+        env.query_total_assets(&wasm, &contract_addr).unwrap(),
+        Uint128::zero()
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    env.send(&contract_addr, coin(1_000_000u128, "uosmo"), &env.signer)
+        .unwrap();
+
+    let total_assets = env.query_total_assets(&wasm, &contract_addr).unwrap();
+    assert_eq!(total_assets, env.get_balance(&contract_addr, "uosmo"));
+
+    // The supply includes the performance fee shares the gain has earned
+    let total_supply = env
+        .query_total_vault_token_supply(&wasm, &contract_addr)
+        .unwrap();
+    assert!(total_supply > env.get_total_supply(&strategy_denom));
+
+    // Conversions carry no fees and round in favour of the vault
+    let amount = Uint128::from(1_000_000u128);
+    let shares = env
+        .query_convert_to_shares(&wasm, &contract_addr, amount)
+        .unwrap();
+    assert_eq!(
+        shares,
+        amount.multiply_ratio(total_supply + VIRTUAL_SHARES, total_assets + VIRTUAL_ASSETS)
+    );
+    let assets = env
+        .query_convert_to_assets(&wasm, &contract_addr, shares)
+        .unwrap();
+    assert!(assets <= amount);
+    assert!(assets + Uint128::from(2u128) >= amount);
+
+    let preview = env
+        .query_preview_deposit(&wasm, &contract_addr, amount)
+        .unwrap();
+    assert!(preview < shares);
+}
@@ -1,6 +1,8 @@
 mod helpers;
 extern crate example_vault;
+use cosmwasm_std::{coin, Uint128};
 use example_vault::config::MyConfig;
+use example_vault::msg::InstantiateMsg;
 use example_vault::state::MyState;
 use helpers::setup::TestEnv;
 use osmosis_test_tube::{Account, Module, Wasm};
@@ -35,6 +37,7 @@ fn query_config() {
 
     assert_eq!(config.strategy_denom, Some(expected_strategy_denom));
     assert_eq!(config.base_denom, "uosmo".to_string());
+    assert_eq!(config.strategy_cap, None);
     assert_eq!(config.test, "hello".to_string());
 }
 
@@ -59,3 +62,40 @@ fn query_state() {
     assert_eq!(state.is_paused, true);
     assert_eq!(state.last_pause, *timestamp);
 }
+
+#[test]
+fn query_remaining_capacity() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(30_000_000u128)),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let remaining_capacity = env.query_remaining_capacity(&wasm, &contract_addr).unwrap();
+    assert_eq!(remaining_capacity, Some(Uint128::from(30_000_000u128)));
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(20_000_000u128, "uosmo"),
+        &env.signer,
+    )
+    .unwrap();
+
+    let remaining_capacity = env.query_remaining_capacity(&wasm, &contract_addr).unwrap();
+    assert_eq!(remaining_capacity, Some(Uint128::from(10_000_000u128)));
+}
+
+#[test]
+fn query_remaining_capacity_uncapped() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let remaining_capacity = env.query_remaining_capacity(&wasm, &contract_addr).unwrap();
+    assert_eq!(remaining_capacity, None);
+}
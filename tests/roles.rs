@@ -0,0 +1,306 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_controllers::AdminError;
+use example_vault::errors::ContractError as MyContractError;
+use example_vault::msg::UpdateConfigMsg;
+use example_vault::roles::Role;
+use helpers::helpers::{assert_err, contains_event_with_attributes};
+use helpers::setup::TestEnv;
+use osmosis_test_tube::{Account, Module, Wasm};
+use std::str::FromStr;
+use vaultenator::errors::ContractError;
+
+#[test]
+fn grant_and_revoke_role() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let pauser = env.traders[0].address();
+
+    assert!(!env
+        .query_has_role(&wasm, &contract_addr, Role::Pauser, pauser.clone())
+        .unwrap());
+    // The owner holds every role without being granted it
+    assert!(env
+        .query_has_role(&wasm, &contract_addr, Role::Pauser, env.signer.address())
+        .unwrap());
+
+    let res = env
+        .grant_role(
+            &wasm,
+            &contract_addr,
+            Role::Pauser,
+            pauser.clone(),
+            &env.signer,
+        )
+        .unwrap();
+    assert!(contains_event_with_attributes(
+        &res,
+        "grant_role",
+        vec![("role", "pauser"), ("address", &pauser)],
+    ));
+
+    assert!(env
+        .query_has_role(&wasm, &contract_addr, Role::Pauser, pauser.clone())
+        .unwrap());
+    // Roles are granted separately
+    assert!(!env
+        .query_has_role(&wasm, &contract_addr, Role::ConfigAdmin, pauser.clone())
+        .unwrap());
+
+    let res = env
+        .revoke_role(
+            &wasm,
+            &contract_addr,
+            Role::Pauser,
+            pauser.clone(),
+            &env.signer,
+        )
+        .unwrap();
+    assert!(contains_event_with_attributes(
+        &res,
+        "revoke_role",
+        vec![("role", "pauser"), ("address", &pauser)],
+    ));
+
+    assert!(!env
+        .query_has_role(&wasm, &contract_addr, Role::Pauser, pauser)
+        .unwrap());
+}
+
+#[test]
+fn grant_role_not_owner() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let res_err = env
+        .grant_role(
+            &wasm,
+            &contract_addr,
+            Role::ConfigAdmin,
+            env.traders[0].address(),
+            &env.traders[0],
+        )
+        .unwrap_err();
+    assert_err(res_err, MyContractError::Admin(AdminError::NotAdmin {}));
+
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::ConfigAdmin,
+        env.traders[0].address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    let res_err = env
+        .revoke_role(
+            &wasm,
+            &contract_addr,
+            Role::ConfigAdmin,
+            env.traders[0].address(),
+            &env.traders[1],
+        )
+        .unwrap_err();
+    assert_err(res_err, MyContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn query_role_members() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+
+    let mut members: Vec<Addr> = env.traders[..3]
+        .iter()
+        .map(|trader| Addr::unchecked(trader.address()))
+        .collect();
+    for member in &members {
+        env.grant_role(
+            &wasm,
+            &contract_addr,
+            Role::Strategist,
+            member.to_string(),
+            &env.signer,
+        )
+        .unwrap();
+    }
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        env.traders[3].address(),
+        &env.signer,
+    )
+    .unwrap();
+    members.sort();
+
+    let res = env
+        .query_role_members(&wasm, &contract_addr, Role::Strategist, None, None)
+        .unwrap();
+    assert_eq!(res, members);
+
+    let res = env
+        .query_role_members(
+            &wasm,
+            &contract_addr,
+            Role::Strategist,
+            Some(members[0].to_string()),
+            Some(1),
+        )
+        .unwrap();
+    assert_eq!(res, vec![members[1].clone()]);
+}
+
+#[test]
+fn config_admin_cannot_update_fees() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let config_admin = &env.traders[0];
+
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::ConfigAdmin,
+        config_admin.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    env.update_config(
+        &wasm,
+        &contract_addr,
+        UpdateConfigMsg {
+            strategy_cap: Some(Some(Uint128::from(1_000u128))),
+            ..Default::default()
+        },
+        config_admin,
+    )
+    .unwrap();
+
+    let res_err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                deposit_fee: Some(Decimal::from_str("0.01").unwrap()),
+                fee_recipient: Some(Some(env.signer.address())),
+                ..Default::default()
+            },
+            config_admin,
+        )
+        .unwrap_err();
+    assert_err(res_err, MyContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn fee_manager_cannot_update_settings() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let fee_manager = &env.traders[0];
+
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::FeeManager,
+        fee_manager.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    env.update_config(
+        &wasm,
+        &contract_addr,
+        UpdateConfigMsg {
+            deposit_fee: Some(Decimal::from_str("0.01").unwrap()),
+            fee_recipient: Some(Some(env.signer.address())),
+            ..Default::default()
+        },
+        fee_manager,
+    )
+    .unwrap();
+
+    // Updates mixing fees and settings need both roles
+    let res_err = env
+        .update_config(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                deposit_fee: Some(Decimal::from_str("0.02").unwrap()),
+                fee_recipient: Some(Some(env.signer.address())),
+                strategy_cap: Some(Some(Uint128::from(1_000u128))),
+                ..Default::default()
+            },
+            fee_manager,
+        )
+        .unwrap_err();
+    assert_err(res_err, MyContractError::Admin(AdminError::NotAdmin {}));
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.deposit_fee, Decimal::from_str("0.01").unwrap());
+}
+
+#[test]
+fn pauser_can_open_pause_and_unpause() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let pauser = &env.traders[0];
+
+    let res_err = env.set_open(&wasm, &contract_addr, pauser).unwrap_err();
+    assert_err(res_err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Pauser,
+        pauser.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    env.set_open(&wasm, &contract_addr, pauser).unwrap();
+    env.set_pause(&wasm, &contract_addr, pauser).unwrap();
+    assert!(env.query_state(&wasm, &contract_addr).unwrap().is_paused);
+
+    env.set_unpause(&wasm, &contract_addr, pauser).unwrap();
+    let state = env.query_state(&wasm, &contract_addr).unwrap();
+    assert!(state.is_open);
+    assert!(!state.is_paused);
+}
+
+#[test]
+fn guardian_can_pause_but_not_unpause() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract(&wasm);
+    let guardian = &env.traders[0];
+
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Guardian,
+        guardian.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    let res_err = env.set_open(&wasm, &contract_addr, guardian).unwrap_err();
+    assert_err(res_err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    env.set_open(&wasm, &contract_addr, &env.signer).unwrap();
+    env.set_pause(&wasm, &contract_addr, guardian).unwrap();
+    assert!(env.query_state(&wasm, &contract_addr).unwrap().is_paused);
+
+    let res_err = env
+        .set_unpause(&wasm, &contract_addr, guardian)
+        .unwrap_err();
+    assert_err(res_err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    env.set_unpause(&wasm, &contract_addr, &env.signer).unwrap();
+    assert!(!env.query_state(&wasm, &contract_addr).unwrap().is_paused);
+}
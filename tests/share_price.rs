@@ -0,0 +1,208 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{coin, Decimal, SignedDecimal, Uint128};
+use example_vault::msg::InstantiateMsg;
+use example_vault::roles::Role;
+use helpers::setup::TestEnv;
+use osmosis_test_tube::{Account, Module, Wasm};
+use std::str::FromStr;
+
+const DAY: u64 = 86_400;
+
+#[test]
+fn share_price_history_and_apr() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+    let depositor = &env.traders[0];
+
+    assert_eq!(env.query_apr(&wasm, &contract_addr, DAY).unwrap(), None);
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000u128, "uosmo"),
+        depositor,
+    )
+    .unwrap();
+    let first_snapshot_at = env.app.get_block_timestamp();
+
+    // A single snapshot has no return to annualise
+    assert_eq!(env.query_apr(&wasm, &contract_addr, DAY).unwrap(), None);
+
+    // 10% yield over a day
+    env.send(&contract_addr, coin(1_000_000u128, "uosmo"), &env.signer)
+        .unwrap();
+    env.app.increase_time(DAY);
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(1_000_000u128, "uosmo"),
+        depositor,
+    )
+    .unwrap();
+    let second_snapshot_at = env.app.get_block_timestamp();
+
+    // Snapshots are taken before the deposit is priced
+    let history = env
+        .query_share_price_history(&wasm, &contract_addr, None, None)
+        .unwrap();
+    let snapshots: Vec<_> = history
+        .iter()
+        .map(|snapshot| {
+            (
+                snapshot.id,
+                snapshot.timestamp,
+                snapshot.total_assets,
+                snapshot.total_supply,
+            )
+        })
+        .collect();
+    assert_eq!(
+        snapshots,
+        vec![
+            (1, first_snapshot_at, Uint128::zero(), Uint128::zero()),
+            (
+                2,
+                second_snapshot_at,
+                Uint128::from(11_000_000u128),
+                Uint128::from(10_000_000_000_000u128)
+            ),
+        ]
+    );
+
+    let apr = env
+        .query_apr(&wasm, &contract_addr, 2 * DAY)
+        .unwrap()
+        .unwrap();
+    assert_eq!(apr.start, first_snapshot_at);
+    assert_eq!(apr.end, second_snapshot_at);
+    assert!(apr.apr > SignedDecimal::from_str("36").unwrap());
+    assert!(apr.apr < SignedDecimal::from_str("36.5").unwrap());
+
+    // Windows that only cover the latest snapshot have no return
+    assert_eq!(env.query_apr(&wasm, &contract_addr, 1).unwrap(), None);
+
+    let page = env
+        .query_share_price_history(&wasm, &contract_addr, Some(1), Some(1))
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].id, 2);
+}
+
+#[test]
+fn apr_is_negative_when_share_price_falls() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            management_fee: Some(Decimal::percent(2)),
+            fee_recipient: Some(env.traders[9].address()),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    // Management fee shares dilute depositors without any yield
+    env.app.increase_time(DAY);
+    env.accrue_fees(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let apr = env
+        .query_apr(&wasm, &contract_addr, 2 * DAY)
+        .unwrap()
+        .unwrap();
+    assert!(apr.apr < SignedDecimal::zero());
+}
+
+#[test]
+fn snapshots_respect_interval() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            share_price_snapshot_interval: Some(DAY),
+            ..env.instantiate_msg()
+        },
+    );
+
+    for _ in 0..3 {
+        env.deposit(
+            &wasm,
+            &contract_addr,
+            coin(1_000_000u128, "uosmo"),
+            &env.traders[0],
+        )
+        .unwrap();
+    }
+    let history = env
+        .query_share_price_history(&wasm, &contract_addr, None, None)
+        .unwrap();
+    assert_eq!(history.len(), 1);
+
+    env.app.increase_time(DAY);
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(1_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+    let history = env
+        .query_share_price_history(&wasm, &contract_addr, None, None)
+        .unwrap();
+    assert_eq!(history.len(), 2);
+}
+
+#[test]
+fn harvest_snapshots_harvested_assets() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_open_contract(&wasm);
+    env.grant_role(
+        &wasm,
+        &contract_addr,
+        Role::Keeper,
+        env.signer.address(),
+        &env.signer,
+    )
+    .unwrap();
+
+    env.deposit(
+        &wasm,
+        &contract_addr,
+        coin(10_000_000u128, "uosmo"),
+        &env.traders[0],
+    )
+    .unwrap();
+
+    // Rewards held by the vault are priced in once harvested
+    env.send(&contract_addr, coin(1_000_000u128, "uosmo"), &env.signer)
+        .unwrap();
+    env.app.increase_time(DAY);
+    env.harvest(&wasm, &contract_addr, &env.signer).unwrap();
+
+    let history = env
+        .query_share_price_history(&wasm, &contract_addr, None, None)
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1].total_assets, Uint128::from(11_000_000u128));
+    assert_eq!(
+        history[1].total_supply,
+        Uint128::from(10_000_000_000_000u128)
+    );
+
+    let apr = env
+        .query_apr(&wasm, &contract_addr, 2 * DAY)
+        .unwrap()
+        .unwrap();
+    assert!(apr.apr > SignedDecimal::zero());
+}
@@ -0,0 +1,221 @@
+mod helpers;
+extern crate example_vault;
+use cosmwasm_std::{Decimal, Uint128};
+use cw_controllers::AdminError;
+use cw_utils::Duration;
+use example_vault::errors::ContractError;
+use example_vault::msg::{InstantiateMsg, UpdateConfigMsg};
+use example_vault::timelock::MAX_CONFIG_TIMELOCK;
+use helpers::helpers::{assert_err, contains_event, contains_event_with_attributes};
+use helpers::setup::{TestEnv, CONFIG_TIMELOCK};
+use osmosis_test_tube::{Account, Module, Wasm};
+use std::str::FromStr;
+
+#[test]
+fn update_config_timelocked() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(1_000_000u128)),
+            config_timelock: Some(CONFIG_TIMELOCK),
+            ..env.instantiate_msg()
+        },
+    );
+
+    for msg in [
+        UpdateConfigMsg {
+            lockup_duration: Some(Duration::Time(100)),
+            ..Default::default()
+        },
+        UpdateConfigMsg {
+            strategy_cap: Some(Some(Uint128::from(2_000_000u128))),
+            ..Default::default()
+        },
+        UpdateConfigMsg {
+            strategy_cap: Some(None),
+            ..Default::default()
+        },
+        UpdateConfigMsg {
+            config_timelock: Some(0),
+            ..Default::default()
+        },
+    ] {
+        let err = env
+            .update_config(&wasm, &contract_addr, msg, &env.signer)
+            .unwrap_err();
+        assert_err(err, ContractError::ConfigChangeTimelocked {});
+    }
+
+    // Tightening changes apply immediately
+    env.update_config(
+        &wasm,
+        &contract_addr,
+        UpdateConfigMsg {
+            strategy_cap: Some(Some(Uint128::from(500_000u128))),
+            config_timelock: Some(CONFIG_TIMELOCK * 2),
+            ..Default::default()
+        },
+        &env.signer,
+    )
+    .unwrap();
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.strategy_cap, Some(Uint128::from(500_000u128)));
+    assert_eq!(config.config_timelock, CONFIG_TIMELOCK * 2);
+}
+
+#[test]
+fn propose_and_execute_config_change() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(1_000_000u128)),
+            config_timelock: Some(CONFIG_TIMELOCK),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let msg = UpdateConfigMsg {
+        deposit_fee: Some(Decimal::from_str("0.01").unwrap()),
+        fee_recipient: Some(Some(env.traders[9].address())),
+        ..Default::default()
+    };
+    let res = env
+        .propose_config_change(&wasm, &contract_addr, msg.clone(), &env.signer)
+        .unwrap();
+    assert!(contains_event_with_attributes(
+        &res,
+        "propose_config_change",
+        vec![("id", "1")],
+    ));
+
+    let changes = env
+        .query_pending_config_changes(&wasm, &contract_addr, None, None)
+        .unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].msg, msg);
+
+    let err = env
+        .execute_config_change(&wasm, &contract_addr, 1, &env.signer)
+        .unwrap_err();
+    assert_err(
+        err,
+        ContractError::ConfigChangeNotReady {
+            id: 1,
+            executable_at: changes[0].executable_at,
+        },
+    );
+
+    env.app.increase_time(CONFIG_TIMELOCK);
+
+    let res = env
+        .execute_config_change(&wasm, &contract_addr, 1, &env.signer)
+        .unwrap();
+    assert!(contains_event(&res, "execute_config_change"));
+    assert!(contains_event(&res, "update_config"));
+
+    let config = env.query_config(&wasm, &contract_addr).unwrap();
+    assert_eq!(config.deposit_fee, Decimal::from_str("0.01").unwrap());
+    assert!(env
+        .query_pending_config_changes(&wasm, &contract_addr, None, None)
+        .unwrap()
+        .is_empty());
+
+    // Executed changes cannot be executed again
+    let err = env
+        .execute_config_change(&wasm, &contract_addr, 1, &env.signer)
+        .unwrap_err();
+    assert_err(err, ContractError::ConfigChangeNotFound { id: 1 });
+}
+
+#[test]
+fn cancel_config_change() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(1_000_000u128)),
+            config_timelock: Some(CONFIG_TIMELOCK),
+            ..env.instantiate_msg()
+        },
+    );
+
+    env.propose_config_change(
+        &wasm,
+        &contract_addr,
+        UpdateConfigMsg {
+            lockup_duration: Some(Duration::Time(100)),
+            ..Default::default()
+        },
+        &env.signer,
+    )
+    .unwrap();
+
+    let err = env
+        .cancel_config_change(&wasm, &contract_addr, 1, &env.traders[0])
+        .unwrap_err();
+    assert_err(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    let res = env
+        .cancel_config_change(&wasm, &contract_addr, 1, &env.signer)
+        .unwrap();
+    assert!(contains_event(&res, "cancel_config_change"));
+
+    env.app.increase_time(CONFIG_TIMELOCK);
+
+    let err = env
+        .execute_config_change(&wasm, &contract_addr, 1, &env.signer)
+        .unwrap_err();
+    assert_err(err, ContractError::ConfigChangeNotFound { id: 1 });
+}
+
+#[test]
+fn propose_config_change_invalid() {
+    let env = TestEnv::new();
+    let wasm = Wasm::new(&env.app);
+    let contract_addr = env.deploy_contract_with_msg(
+        &wasm,
+        &InstantiateMsg {
+            strategy_cap: Some(Uint128::from(1_000_000u128)),
+            config_timelock: Some(CONFIG_TIMELOCK),
+            ..env.instantiate_msg()
+        },
+    );
+
+    let err = env
+        .propose_config_change(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                lockup_duration: Some(Duration::Time(100)),
+                ..Default::default()
+            },
+            &env.traders[0],
+        )
+        .unwrap_err();
+    assert_err(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    // Changes are validated when proposed
+    let err = env
+        .propose_config_change(
+            &wasm,
+            &contract_addr,
+            UpdateConfigMsg {
+                config_timelock: Some(MAX_CONFIG_TIMELOCK + 1),
+                ..Default::default()
+            },
+            &env.signer,
+        )
+        .unwrap_err();
+    assert_err(
+        err,
+        ContractError::InvalidConfigTimelock {
+            max: MAX_CONFIG_TIMELOCK,
+        },
+    );
+}